@@ -0,0 +1,127 @@
+use alloy::primitives::{Address, ChainId};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// Labels for known addresses, per chain, stored as JSON under the user config directory:
+///
+/// ```json
+/// { "1": { "0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC": "Treasury" } }
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+    entries: BTreeMap<ChainId, BTreeMap<Address, String>>,
+}
+
+impl AddressBook {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("safe-hash").join("addressbook.json"))
+    }
+
+    /// Returns `path`, or the default location when none was given.
+    pub fn resolve_path(path: Option<PathBuf>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        path.or_else(Self::default_path)
+            .ok_or_else(|| "could not determine the config directory, pass --address-book".into())
+    }
+
+    /// Loads the address book at `path`, an absent file being an empty book.
+    pub fn load(path: &PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read address book {}: {}", path.display(), e))?;
+        let book = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse address book {}: {}", path.display(), e))?;
+        Ok(book)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn label(&self, chain_id: ChainId, address: Address) -> Option<&str> {
+        self.entries.get(&chain_id)?.get(&address).map(String::as_str)
+    }
+
+    /// Formats an address followed by its label, if it has one.
+    pub fn display(&self, chain_id: ChainId, address: Address) -> String {
+        match self.label(chain_id, address) {
+            Some(label) => format!("{} ({})", address, label),
+            None => address.to_string(),
+        }
+    }
+
+    /// Adds or relabels an entry, returning the previous label.
+    pub fn insert(&mut self, chain_id: ChainId, address: Address, label: String) -> Option<String> {
+        self.entries.entry(chain_id).or_default().insert(address, label)
+    }
+
+    /// Removes an entry, returning its label.
+    pub fn remove(&mut self, chain_id: ChainId, address: Address) -> Option<String> {
+        let chain = self.entries.get_mut(&chain_id)?;
+        let label = chain.remove(&address);
+        if chain.is_empty() {
+            self.entries.remove(&chain_id);
+        }
+        label
+    }
+
+    /// Iterates over `(chain id, address, label)` entries, ordered by chain id then address.
+    pub fn entries(&self) -> impl Iterator<Item = (ChainId, Address, &str)> {
+        self.entries.iter().flat_map(|(chain_id, labels)| {
+            labels.iter().map(|(address, label)| (*chain_id, *address, label.as_str()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn test_load_and_label() {
+        let path = std::env::temp_dir().join(format!("safe-hash-book-{}.json", std::process::id()));
+        fs::write(&path, r#"{"1":{"0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC":"Treasury"}}"#)
+            .unwrap();
+
+        let book = AddressBook::load(&path).unwrap();
+        let treasury = address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC");
+        assert_eq!(book.label(1, treasury), Some("Treasury"));
+        assert_eq!(book.label(10, treasury), None);
+        assert_eq!(book.display(1, treasury), format!("{} (Treasury)", treasury));
+        assert_eq!(book.display(10, treasury), treasury.to_string());
+        fs::remove_file(&path).unwrap();
+
+        assert!(AddressBook::load(&path).unwrap().label(1, treasury).is_none());
+    }
+
+    #[test]
+    fn test_insert_remove_and_save() {
+        let path =
+            std::env::temp_dir().join(format!("safe-hash-book-save-{}.json", std::process::id()));
+        let alice = address!("0xc12F6A2D93C0788366FC91aeBf21b33CCCb0c0d8");
+        let bob = address!("0xAF43958ad62389BE3E0B553dFd259Ec335814c1C");
+
+        let mut book = AddressBook::default();
+        assert_eq!(book.insert(1, alice, "Alice".to_string()), None);
+        assert_eq!(book.insert(1, alice, "Alice (ledger)".to_string()), Some("Alice".to_string()));
+        book.insert(10, bob, "Bob".to_string());
+        book.save(&path).unwrap();
+
+        let mut book = AddressBook::load(&path).unwrap();
+        assert_eq!(book.entries().collect::<Vec<_>>(), vec![
+            (1, alice, "Alice (ledger)"),
+            (10, bob, "Bob")
+        ]);
+        assert_eq!(book.remove(10, bob), Some("Bob".to_string()));
+        assert_eq!(book.remove(10, bob), None);
+        assert_eq!(book.entries().count(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
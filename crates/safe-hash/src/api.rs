@@ -1,6 +1,6 @@
 use crate::{cli::TransactionArgs, output::Mismatch};
 use alloy::primitives::{Address, FixedBytes, U256, hex};
-use safe_utils::get_safe_api;
+use safe_utils::{SafeOwnership, SafeWalletVersion, get_safe_api};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -74,6 +74,62 @@ pub struct SafeApiResponse {
     pub results: Vec<SafeTransaction>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeInfo {
+    pub address: Address,
+    #[serde(deserialize_with = "u64_from_number_or_string")]
+    pub nonce: u64,
+    #[serde(deserialize_with = "u64_from_number_or_string")]
+    pub threshold: u64,
+    pub owners: Vec<Address>,
+    pub master_copy: Option<Address>,
+    #[serde(default)]
+    pub modules: Vec<Address>,
+    pub fallback_handler: Option<Address>,
+    pub guard: Option<Address>,
+    pub version: Option<String>,
+}
+
+impl SafeInfo {
+    /// Version, owners and threshold for verifying signatures, `None` when the version is
+    /// unknown.
+    pub fn ownership(&self) -> Option<SafeOwnership> {
+        // Drop build metadata such as `+L2`
+        let version = self.version.as_deref()?.split('+').next()?;
+        Some(SafeOwnership {
+            version: SafeWalletVersion::parse(version).ok()?,
+            owners: self.owners.clone(),
+            threshold: self.threshold,
+        })
+    }
+}
+
+/// The transaction service returns some counters as numbers and, in newer versions, as strings.
+fn u64_from_number_or_string<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) => {
+            n.as_u64().ok_or_else(|| serde::de::Error::custom("expected an unsigned integer"))
+        }
+        serde_json::Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        other => Err(serde::de::Error::custom(format!("expected a number, got {other}"))),
+    }
+}
+
+pub fn get_safe_info(
+    chain_id: u64,
+    safe_address: Address,
+) -> Result<SafeInfo, Box<dyn std::error::Error>> {
+    let api_url = get_safe_api(chain_id)?;
+    let url = format!("{}/api/v1/safes/{}/", api_url, safe_address);
+    println!("Fetching Safe info from API: {}", url);
+    let response = reqwest::blocking::get(&url)?.error_for_status()?;
+    Ok(response.json()?)
+}
+
 pub fn get_safe_transaction(
     chain_id: u64,
     safe_address: Address,
@@ -226,6 +282,7 @@ pub fn validate_safe_tx_hash(
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
     use std::fs;
@@ -254,6 +311,29 @@ mod tests {
         assert_eq!(tx.is_successful, Some(true));
     }
 
+    #[test]
+    fn test_decode_safe_info_response() {
+        let json = fs::read_to_string("../../test/client_safe_info_response.json")
+            .expect("Failed to read test file");
+        let info: SafeInfo = serde_json::from_str(&json).expect("Failed to decode JSON");
+
+        assert_eq!(
+            info.address,
+            Address::from_str("0x1c694Fc3006D81ff4a56F97E1b99529066a23725").unwrap()
+        );
+        assert_eq!(info.nonce, 64);
+        assert_eq!(info.threshold, 2);
+        assert_eq!(info.owners.len(), 3);
+        assert_eq!(info.version.as_deref(), Some("1.3.0+L2"));
+        let ownership = info.ownership().unwrap();
+        assert_eq!(ownership.version, SafeWalletVersion::new(1, 3, 0));
+        assert_eq!(ownership.threshold, 2);
+
+        let json = json.replace("\"nonce\": \"64\"", "\"nonce\": 64");
+        let info: SafeInfo = serde_json::from_str(&json).expect("Failed to decode JSON");
+        assert_eq!(info.nonce, 64);
+    }
+
     fn create_test_tx() -> SafeTransaction {
         SafeTransaction {
             safe: Address::from_str("0x1c694Fc3006D81ff4a56F97E1b99529066a23725").unwrap(),
@@ -517,4 +597,19 @@ mod tests {
 
         assert!(validate_safe_tx_hash(&api_tx, &calculated_hash).is_ok());
     }
+
+    #[test]
+    fn test_safe_tx_hash_mismatch_blocks_signing() {
+        let mut api_tx = create_test_tx();
+        api_tx.safe_tx_hash =
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string();
+
+        let mut warnings = crate::output::SafeWarnings::new();
+        warnings.zero_value = true;
+        assert!(!warnings.blocks_signing());
+
+        let calculated_hash = FixedBytes::repeat_byte(0xab);
+        warnings.argument_mismatches.extend(validate_safe_tx_hash(&api_tx, &calculated_hash).err());
+        assert!(warnings.blocks_signing());
+    }
 }
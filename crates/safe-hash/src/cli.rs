@@ -1,8 +1,20 @@
 use std::path::PathBuf;
 
-use alloy::primitives::{Address, U256};
+use crate::{
+    device::Device,
+    etherscan::VerifierKind,
+    exec_tx::parse_wei,
+    msg_signing::MessageEncoding,
+    nested::{NestedApproval, nested_levels},
+    signer::SignerArgs,
+    tokens::parse_units,
+};
+use alloy::{
+    hex,
+    primitives::{Address, B256, U256},
+};
 use clap::{Parser, Subcommand};
-use safe_utils::{SafeWalletVersion, get_all_supported_chain_names};
+use safe_utils::{OwnerSignature, SafeWalletVersion, get_all_supported_chain_names};
 use semver::Version;
 
 #[derive(Parser, Debug)]
@@ -13,6 +25,7 @@ pub struct CliArgs {
 }
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Mode {
     /// Transaction signing mode
     #[command(name = "tx")]
@@ -25,14 +38,43 @@ pub enum Mode {
     /// Encode EIP 712 typed message
     #[command(name = "typed")]
     Eip712(Eip712Args),
+
+    /// Manage the local address book of labeled addresses
+    #[command(name = "addressbook")]
+    AddressBook(AddressBookArgs),
+
+    /// Verify a transaction or message, then sign its Safe hash with a keystore or mnemonic
+    #[command(name = "sign")]
+    Sign(SignArgs),
+
+    /// Work with the owner signatures of a Safe transaction
+    #[command(name = "signatures")]
+    Signatures(SignaturesArgs),
+
+    /// Build the unsigned transaction an executor sends to call `execTransaction`
+    #[command(name = "exec-tx")]
+    ExecTx(ExecTxArgs),
+
+    /// Decode and hash calldata sent to a Safe module (execTransactionFromModule, Zodiac Delay and
+    /// Roles, AllowanceModule)
+    #[command(name = "module")]
+    Module(ModuleArgs),
+
+    /// Predict the address of a new Safe, or decode `createProxyWithNonce` calldata
+    #[command(name = "predict")]
+    Predict(PredictArgs),
+
+    /// Hash the Safe transaction or message behind a dapp's JSON-RPC or WalletConnect request
+    #[command(name = "rpc-request")]
+    RpcRequest(RpcRequestArgs),
 }
 
 #[derive(Parser, Debug)]
 pub struct TransactionArgs {
     /// Chain
-    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea,
-    /// mantle, optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
-    /// gnosis-chiado, polygon-zkevm
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
     #[arg(short, long, required = true)]
     pub chain: String,
 
@@ -64,13 +106,22 @@ pub struct TransactionArgs {
     #[arg(long, default_value_t = 0)]
     pub operation: u8,
 
-    /// Nested safe address
+    /// Nested safe address, repeatable for Safes owned by Safes: the owner of the Safe comes
+    /// first, then its owner and so on
     #[arg(long)]
-    pub nested_safe_address: Option<Address>,
+    pub nested_safe_address: Vec<Address>,
 
-    /// Nested safe nonce
+    /// Nested safe nonce, one per `approve-hash` level, in order
     #[arg(long)]
-    pub nested_safe_nonce: Option<u64>,
+    pub nested_safe_nonce: Vec<u64>,
+
+    /// Nested safe version, one per level, defaulting to `--safe-version`
+    #[arg(long)]
+    pub nested_safe_version: Vec<SafeWalletVersion>,
+
+    /// How each nested safe approves the level before it, defaulting to `approve-hash`
+    #[arg(long)]
+    pub nested_approval: Vec<NestedApproval>,
 
     #[arg(long, default_value_t = U256::ZERO)]
     pub safe_tx_gas: U256,
@@ -89,6 +140,55 @@ pub struct TransactionArgs {
 
     #[arg(long)]
     pub offline: bool,
+
+    /// Contract verification providers to try in order, comma separated
+    /// (etherscan, sourcify, blockscout). Defaults depend on the chain.
+    #[arg(long, value_delimiter = ',')]
+    pub verifier: Vec<VerifierKind>,
+
+    /// Do not read or write the on-disk contract verification cache
+    #[arg(long)]
+    pub no_verification_cache: bool,
+
+    /// Additional token list JSON files (`{"tokens": [...]}`), taking precedence over the bundled
+    /// list
+    #[arg(long)]
+    pub token_list: Vec<PathBuf>,
+
+    /// Warn on ERC-20 approvals above this many whole tokens (e.g. 10000 or 0.5)
+    #[arg(long, value_parser = parse_token_amount)]
+    pub approval_threshold: Option<String>,
+
+    /// Address book JSON file, defaults to `safe-hash/addressbook.json` in the config directory
+    #[arg(long)]
+    pub address_book: Option<PathBuf>,
+
+    /// Format the hashes the way this hardware wallet displays them
+    #[arg(long)]
+    pub device: Option<Device>,
+
+    /// Print every step of the EIP-712 encoding of the transaction
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Execute the transaction in a local EVM on top of the latest block of `--rpc-url`, without
+    /// checking the owners' signatures
+    #[arg(long, requires = "rpc_url")]
+    pub simulate: bool,
+
+    /// JSON-RPC endpoint, e.g. a local anvil node, to check the Safe's nonce, owners, version and
+    /// domain separator against and to simulate on
+    #[arg(long)]
+    pub rpc_url: Option<String>,
+}
+
+fn parse_token_amount(amount: &str) -> Result<String, String> {
+    let decimals = amount.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+    u8::try_from(decimals)
+        .ok()
+        .and_then(|decimals| parse_units(amount, decimals))
+        .map(|_| amount.to_string())
+        .ok_or_else(|| format!("{amount:?} is not a decimal token amount"))
 }
 
 impl Default for TransactionArgs {
@@ -107,9 +207,20 @@ impl Default for TransactionArgs {
             safe_address: Address::ZERO,
             chain: "ethereum".to_string(),
             safe_version: Version::new(1, 3, 0),
-            nested_safe_address: None,
-            nested_safe_nonce: None,
+            nested_safe_address: Vec::new(),
+            nested_safe_nonce: Vec::new(),
+            nested_safe_version: Vec::new(),
+            nested_approval: Vec::new(),
             offline: false,
+            verifier: Vec::new(),
+            no_verification_cache: false,
+            token_list: Vec::new(),
+            approval_threshold: None,
+            address_book: None,
+            device: None,
+            explain: false,
+            simulate: false,
+            rpc_url: None,
         }
     }
 }
@@ -117,9 +228,9 @@ impl Default for TransactionArgs {
 #[derive(Parser, Debug)]
 pub struct MessageArgs {
     /// Chain
-    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea,
-    /// mantle, optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
-    /// gnosis-chiado, polygon-zkevm
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
     #[arg(short, long, required = true)]
     pub chain: String,
 
@@ -134,75 +245,466 @@ pub struct MessageArgs {
     /// Path to the message file to be signed
     #[arg(short, long, required = true)]
     pub input_file: String,
+
+    /// Encoding of the message file
+    #[arg(long, default_value = "utf8")]
+    pub encoding: MessageEncoding,
+
+    /// Sign the text exactly as in the file, including leading and trailing whitespace
+    #[arg(long)]
+    pub no_trim: bool,
+
+    /// Format the hashes the way this hardware wallet displays them
+    #[arg(long)]
+    pub device: Option<Device>,
+
+    /// JSON-RPC endpoint to check the Safe's version and domain separator against
+    #[arg(long)]
+    pub rpc_url: Option<String>,
+
+    /// Address book JSON file, defaults to `safe-hash/addressbook.json` in the config directory
+    #[arg(long)]
+    pub address_book: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
 pub struct Eip712Args {
     /// Chain
-    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea,
-    /// mantle, optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
-    /// gnosis-chiado, polygon-zkevm
-    #[arg(short, long)]
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
+    #[arg(short, long, required_unless_present = "standalone")]
     pub chain: Option<String>,
 
     /// Address of the safe address
-    #[arg(short = 's', long = "safe-address")]
+    #[arg(short = 's', long = "safe-address", required_unless_present = "standalone")]
     pub safe_address: Option<Address>,
 
     /// Safe Contract version
-    #[arg(short = 'u', long)]
+    #[arg(short = 'u', long, required_unless_present = "standalone")]
     pub safe_version: Option<SafeWalletVersion>,
 
+    /// Only compute the EIP-712 hash, without wrapping it in a Safe message
     #[arg(long)]
     pub standalone: bool,
 
-    /// File contiaing the JSON formatted EIP 712 spec
+    /// Contract the message is expected to be verified by, compared with the domain's
+    /// `verifyingContract`
+    #[arg(long)]
+    pub expect_contract: Option<Address>,
+
+    /// Print every step of the EIP-712 encoding: type strings, typehashes and encoded data
+    #[arg(long)]
+    pub explain: bool,
+
+    /// File containing the JSON formatted EIP 712 typed data, or a JSON-RPC
+    /// `eth_signTypedData_v4` request. Read from stdin when omitted or `-`
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct SignArgs {
+    #[command(flatten)]
+    pub signer: SignerArgs,
+
+    /// Sign a transaction even when it differs from the API or the chain, rewrites the Safe's
+    /// configuration or has a nonce that is not the next one
+    #[arg(long)]
+    pub sign_despite_warnings: bool,
+
+    #[command(subcommand)]
+    pub target: SignTarget,
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum SignTarget {
+    /// Sign a Safe transaction
+    #[command(name = "tx")]
+    Transaction(TransactionArgs),
+
+    /// Sign a Safe message
+    #[command(name = "msg")]
+    Message(MessageArgs),
+
+    /// Sign an EIP 712 typed message, as a Safe message unless `--standalone`
+    #[command(name = "typed")]
+    Eip712(Eip712Args),
+}
+
+#[derive(Parser, Debug)]
+pub struct SignaturesArgs {
+    #[command(subcommand)]
+    pub action: SignaturesAction,
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum SignaturesAction {
+    /// Sort owner signatures into a `signatures` blob and print the `execTransaction` calldata.
+    /// Confirmations from the transaction service are included unless offline
+    Combine(CombineArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CombineArgs {
+    #[command(flatten)]
+    pub tx: TransactionArgs,
+
+    /// Owner signature, repeatable:
+    /// - `0x<65 bytes>` ECDSA signature, eth_sign or EIP-712 style
+    /// - `approved:<owner>` owner that called `approveHash` or executes the transaction
+    /// - `contract:<owner>:0x<signature>` EIP-1271 signature of a contract owner
+    #[arg(long = "signature", required = true)]
+    pub signatures: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ExecTxArgs {
+    #[command(flatten)]
+    pub tx: TransactionArgs,
+
+    /// Owner signature in the `signatures combine` format, repeatable. Confirmations from the
+    /// transaction service are included unless offline
+    #[arg(long = "signature")]
+    pub signatures: Vec<String>,
+
+    /// Nonce of the executor account
+    #[arg(long)]
+    pub executor_nonce: u64,
+
+    /// Gas limit of the executor transaction
+    #[arg(long)]
+    pub gas_limit: u64,
+
+    /// EIP-1559 max fee per gas, in wei or with a `gwei` suffix
+    #[arg(long, value_parser = parse_wei, required_unless_present = "executor_gas_price")]
+    pub max_fee_per_gas: Option<u128>,
+
+    /// EIP-1559 max priority fee per gas, in wei or with a `gwei` suffix
+    #[arg(long, value_parser = parse_wei, required_unless_present = "executor_gas_price")]
+    pub max_priority_fee_per_gas: Option<u128>,
+
+    /// Gas price of a legacy transaction, in wei or with a `gwei` suffix
+    #[arg(
+        long, value_parser = parse_wei, conflicts_with_all = ["max_fee_per_gas", "max_priority_fee_per_gas"])]
+    pub executor_gas_price: Option<u128>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ModuleArgs {
+    /// Chain
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
     #[arg(short, long, required = true)]
-    pub file: PathBuf,
+    pub chain: String,
+
+    /// Calldata sent to the module entry point, encoded in hex
+    #[arg(short, long, required = true)]
+    pub data: String,
+
+    /// Address of the AllowanceModule, the verifying contract of its transfer hash
+    #[arg(long)]
+    pub module_address: Option<Address>,
+
+    /// Current nonce of the delegate's allowance for the transferred token
+    #[arg(long)]
+    pub allowance_nonce: Option<u16>,
+
+    /// Address book JSON file, defaults to `safe-hash/addressbook.json` in the config directory
+    #[arg(long)]
+    pub address_book: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct PredictArgs {
+    /// Chain
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
+    #[arg(short, long, required = true)]
+    pub chain: String,
+
+    /// Safe Contract version
+    #[arg(short = 'u', long)]
+    pub safe_version: SafeWalletVersion,
+
+    /// Owner of the new Safe, repeatable
+    #[arg(long = "owner", required_unless_present = "calldata")]
+    pub owners: Vec<Address>,
+
+    /// Number of owners required to confirm a transaction
+    #[arg(long, required_unless_present = "calldata")]
+    pub threshold: Option<u64>,
+
+    /// Fallback handler of the new Safe
+    #[arg(long, default_value_t = Address::ZERO)]
+    pub fallback_handler: Address,
+
+    /// Contract delegatecalled during setup, e.g. to enable modules
+    #[arg(long, default_value_t = Address::ZERO)]
+    pub setup_to: Address,
+
+    /// Calldata of the setup delegatecall, encoded in hex
+    #[arg(long, default_value = "0x")]
+    pub setup_data: String,
+
+    /// Salt nonce passed to the proxy factory
+    #[arg(long, default_value_t = U256::ZERO)]
+    pub salt_nonce: U256,
+
+    /// Singleton (mastercopy) the proxy delegates to
+    #[arg(long, required_unless_present = "calldata")]
+    pub singleton: Option<Address>,
+
+    /// Proxy factory deploying the Safe
+    #[arg(long)]
+    pub factory: Option<Address>,
+
+    /// The factory's `proxyCreationCode()`, encoded in hex, to predict the address on EVM chains
+    #[arg(long)]
+    pub proxy_creation_code: Option<String>,
+
+    /// Bytecode hash of the proxy, to predict the address on zkSync
+    #[arg(long)]
+    pub proxy_bytecode_hash: Option<B256>,
+
+    /// Existing `createProxyWithNonce` or `createChainSpecificProxyWithNonce` calldata to decode
+    #[arg(long, conflicts_with_all = ["owners", "threshold", "singleton", "salt_nonce"])]
+    pub calldata: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct RpcRequestArgs {
+    /// File containing the `eth_sendTransaction`, `personal_sign` or `eth_signTypedData_v4`
+    /// request, bare or in a WalletConnect `session_request`. `-` reads stdin
+    pub input: PathBuf,
+
+    /// Chain, defaults to the chain of the WalletConnect request
+    /// - arbitrum, aurora, avalanche, base, blast, bsc, celo, ethereum, gnosis, linea, mantle,
+    ///   optimism, polygon, scroll, sepolia, worldchain, xlayer, zksync, base-sepolia,
+    ///   gnosis-chiado, polygon-zkevm
+    #[arg(short, long)]
+    pub chain: Option<String>,
+
+    /// Safe Contract version
+    #[arg(short = 'u', long)]
+    pub safe_version: SafeWalletVersion,
+
+    /// Nonce the Safe proposes an `eth_sendTransaction` with
+    #[arg(short, long)]
+    pub nonce: Option<u64>,
+
+    /// Address book JSON file, defaults to `safe-hash/addressbook.json` in the config directory
+    #[arg(long)]
+    pub address_book: Option<PathBuf>,
+
+    /// Format the hashes the way this hardware wallet displays them
+    #[arg(long)]
+    pub device: Option<Device>,
+}
+
+/// Parses an owner signature in the `--signature` format, recovering ECDSA signers from
+/// `safe_tx_hash`.
+pub fn parse_owner_signature(input: &str, safe_tx_hash: B256) -> Result<OwnerSignature, String> {
+    let parse_owner = |owner: &str| {
+        owner.parse::<Address>().map_err(|e| format!("invalid owner {:?}: {}", owner, e))
+    };
+    let parse_hex =
+        |data: &str| hex::decode(data).map_err(|e| format!("invalid signature {:?}: {}", data, e));
+
+    if let Some(owner) = input.strip_prefix("approved:") {
+        return Ok(OwnerSignature::approved_hash(parse_owner(owner)?));
+    }
+    if let Some(rest) = input.strip_prefix("contract:") {
+        let (owner, signature) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("expected contract:<owner>:<signature>, got {:?}", input))?;
+        return Ok(OwnerSignature::contract(parse_owner(owner)?, parse_hex(signature)?.into()));
+    }
+    OwnerSignature::ecdsa(&parse_hex(input)?, safe_tx_hash)
+        .map_err(|e| format!("invalid signature {:?}: {}", input, e))
+}
+
+#[derive(Parser, Debug)]
+pub struct AddressBookArgs {
+    /// Address book JSON file, defaults to `safe-hash/addressbook.json` in the config directory
+    #[arg(long, global = true)]
+    pub address_book: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub action: AddressBookAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AddressBookAction {
+    /// Add an address, or relabel it if it is already known
+    Add {
+        /// Chain the label applies to
+        #[arg(short, long)]
+        chain: String,
+
+        #[arg(short, long)]
+        address: Address,
+
+        #[arg(short, long)]
+        label: String,
+    },
+
+    /// Remove an address
+    Remove {
+        /// Chain the label applies to
+        #[arg(short, long)]
+        chain: String,
+
+        #[arg(short, long)]
+        address: Address,
+    },
+
+    /// List labeled addresses
+    List {
+        /// Only list the labels of this chain
+        #[arg(short, long)]
+        chain: Option<String>,
+    },
 }
 
 impl CliArgs {
-    pub fn validate_eip712_args(&self) {
-        if let Mode::Eip712(Eip712Args { chain, safe_address, safe_version, standalone, .. }) =
-            &self.mode
-        {
-            if *standalone {
-                return;
+    /// Transaction arguments, whether in `tx` or `sign tx` mode.
+    fn tx_args(&self) -> Option<&TransactionArgs> {
+        match &self.mode {
+            Mode::Transaction(tx_args)
+            | Mode::Sign(SignArgs { target: SignTarget::Transaction(tx_args), .. }) => {
+                Some(tx_args)
             }
-            if chain.is_none() || safe_address.is_none() || safe_version.is_none() {
-                eprintln!("include `--standalone` flag");
+            _ => None,
+        }
+    }
+
+    fn msg_args(&self) -> Option<&MessageArgs> {
+        match &self.mode {
+            Mode::Message(msg_args)
+            | Mode::Sign(SignArgs { target: SignTarget::Message(msg_args), .. }) => Some(msg_args),
+            _ => None,
+        }
+    }
+
+    fn eip712_args(&self) -> Option<&Eip712Args> {
+        match &self.mode {
+            Mode::Eip712(eip712_args)
+            | Mode::Sign(SignArgs { target: SignTarget::Eip712(eip712_args), .. }) => {
+                Some(eip712_args)
+            }
+            _ => None,
+        }
+    }
+
+    /// Safe address of the mode, checked the same way in every mode.
+    fn safe_address(&self) -> Option<Address> {
+        if let Some(tx_args) = self.tx_args() {
+            Some(tx_args.safe_address)
+        } else if let Some(msg_args) = self.msg_args() {
+            Some(msg_args.safe_address)
+        } else {
+            self.eip712_args().and_then(|eip712_args| eip712_args.safe_address)
+        }
+    }
+
+    pub fn validate_safe_address(&self) {
+        if self.safe_address() == Some(Address::ZERO) {
+            eprintln!("--safe-address cannot be the zero address");
+            std::process::exit(1);
+        }
+    }
+
+    pub fn validate_signer(&self) {
+        if let Mode::Sign(SignArgs { signer, .. }) = &self.mode {
+            if signer.keystore.is_none() && !signer.mnemonic && signer.mnemonic_file.is_none() {
+                eprintln!("pass --keystore, --mnemonic or --mnemonic-file to sign");
+                std::process::exit(1);
+            }
+            if signer.keystore.is_none() && signer.password_file.is_some() {
+                eprintln!(
+                    "--password-file is the keystore password, pass the mnemonic passphrase with --mnemonic-passphrase-file"
+                );
                 std::process::exit(1);
             }
         }
     }
 
     pub fn validate_safe_version(&self) {
-        if let Mode::Transaction(tx_args) = &self.mode {
+        if let Some(tx_args) = self.tx_args() {
             if tx_args.safe_version < SafeWalletVersion::new(0, 1, 0) {
                 eprintln!("{} version of Safe Wallet is not supported", tx_args.safe_version);
                 std::process::exit(1);
             }
+        } else if let Some(safe_version) =
+            self.eip712_args().and_then(|eip712_args| eip712_args.safe_version.as_ref())
+        {
+            if *safe_version < SafeWalletVersion::new(0, 1, 0) {
+                eprintln!("{} version of Safe Wallet is not supported", safe_version);
+                std::process::exit(1);
+            }
+        } else if let Mode::RpcRequest(rpc_args) = &self.mode {
+            if rpc_args.safe_version < SafeWalletVersion::new(0, 1, 0) {
+                eprintln!("{} version of Safe Wallet is not supported", rpc_args.safe_version);
+                std::process::exit(1);
+            }
+        } else if let Mode::Predict(predict_args) = &self.mode {
+            // Earlier versions have no fallback handler in `setup`
+            if predict_args.safe_version < SafeWalletVersion::new(1, 1, 0) {
+                eprintln!("predict supports Safe Wallet 1.1.0 and later");
+                std::process::exit(1);
+            }
         }
     }
 
     pub fn validate_chain(&self) {
-        if let Mode::Transaction(tx_args) = &self.mode {
+        if let Some(tx_args) = self.tx_args() {
             let valid_names = get_all_supported_chain_names();
             if !valid_names.contains(&tx_args.chain) {
                 eprintln!("chain {:?} is not supported", tx_args.chain);
                 std::process::exit(1);
             }
-        } else if let Mode::Message(msg_args) = &self.mode {
+        } else if let Some(msg_args) = self.msg_args() {
             let valid_names = get_all_supported_chain_names();
             if !valid_names.contains(&msg_args.chain) {
                 eprintln!("chain {:?} is not supported", msg_args.chain);
                 std::process::exit(1);
             }
+        } else if let Some(chain) =
+            self.eip712_args().and_then(|eip712_args| eip712_args.chain.as_ref())
+        {
+            let valid_names = get_all_supported_chain_names();
+            if !valid_names.contains(chain) {
+                eprintln!("chain {:?} is not supported", chain);
+                std::process::exit(1);
+            }
+        } else if let Mode::Module(ModuleArgs { chain, .. })
+        | Mode::Predict(PredictArgs { chain, .. })
+        | Mode::RpcRequest(RpcRequestArgs { chain: Some(chain), .. }) = &self.mode
+        {
+            let valid_names = get_all_supported_chain_names();
+            if !valid_names.contains(chain) {
+                eprintln!("chain {:?} is not supported", chain);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn validate_nested_safes(&self) {
+        if let Some(tx_args) = self.tx_args() {
+            if let Err(e) = nested_levels(tx_args) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
         }
     }
 
     pub fn validate_to_for_offline(&self) {
-        if let Mode::Transaction(tx_args) = &self.mode {
+        if let Some(tx_args) = self.tx_args() {
             if tx_args.to.is_none() && tx_args.offline {
                 eprintln!(
                     "--to <address> must be provided in offline mode. When API data cannot be fetched, you must provide the destination address manually."
@@ -216,6 +718,7 @@ impl CliArgs {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::signer::SignatureStyle;
     use alloy::primitives::address;
     use clap::Parser;
 
@@ -276,6 +779,239 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verifier_fallback_chain() {
+        let mut args = manual_args();
+        args.extend_from_slice(&[
+            "--safe-version".to_string(),
+            "1.3.0".to_string(),
+            "--verifier".to_string(),
+            "sourcify,blockscout".to_string(),
+        ]);
+
+        let cli = CliArgs::try_parse_from(&args).unwrap();
+        if let Mode::Transaction(tx_args) = cli.mode {
+            assert_eq!(tx_args.verifier, vec![VerifierKind::Sourcify, VerifierKind::Blockscout]);
+            assert!(!tx_args.no_verification_cache);
+        } else {
+            panic!("Expected Transaction mode");
+        }
+    }
+
+    #[test]
+    fn test_approval_threshold_is_validated() {
+        let mut args = manual_args();
+        args.extend_from_slice(&[
+            "--safe-version".to_string(),
+            "1.3.0".to_string(),
+            "--approval-threshold".to_string(),
+            "10000.5".to_string(),
+        ]);
+        let cli = CliArgs::try_parse_from(&args).unwrap();
+        if let Mode::Transaction(tx_args) = cli.mode {
+            assert_eq!(tx_args.approval_threshold.as_deref(), Some("10000.5"));
+        } else {
+            panic!("Expected Transaction mode");
+        }
+
+        let last = args.len() - 1;
+        args[last] = "lots".to_string();
+        assert!(CliArgs::try_parse_from(&args).is_err());
+    }
+
+    #[test]
+    fn test_simulate_requires_rpc_url() {
+        let mut args = manual_args();
+        args.extend_from_slice(&[
+            "--safe-version".to_string(),
+            "1.4.1".to_string(),
+            "--simulate".to_string(),
+        ]);
+        assert!(CliArgs::try_parse_from(&args).is_err());
+
+        args.extend_from_slice(&["--rpc-url".to_string(), "http://127.0.0.1:8545".to_string()]);
+        let cli = CliArgs::try_parse_from(&args).unwrap();
+        if let Mode::Transaction(tx_args) = cli.mode {
+            assert!(tx_args.simulate);
+            assert_eq!(tx_args.rpc_url.as_deref(), Some("http://127.0.0.1:8545"));
+        } else {
+            panic!("Expected Transaction mode");
+        }
+    }
+
+    #[test]
+    fn test_addressbook_mode() {
+        let args = [
+            "safe-hash",
+            "addressbook",
+            "add",
+            "--chain",
+            "ethereum",
+            "--address",
+            "0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC",
+            "--label",
+            "Treasury",
+            "--address-book",
+            "book.json",
+        ];
+
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        if let Mode::AddressBook(book_args) = cli.mode {
+            assert_eq!(book_args.address_book, Some(PathBuf::from("book.json")));
+            if let AddressBookAction::Add { chain, address, label } = book_args.action {
+                assert_eq!(chain, "ethereum");
+                assert_eq!(address, address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC"));
+                assert_eq!(label, "Treasury");
+            } else {
+                panic!("Expected add action");
+            }
+        } else {
+            panic!("Expected AddressBook mode");
+        }
+    }
+
+    #[test]
+    fn test_parse_owner_signature() {
+        let owner = address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC");
+        let hash = B256::ZERO;
+
+        assert_eq!(
+            parse_owner_signature(&format!("approved:{}", owner), hash),
+            Ok(OwnerSignature::approved_hash(owner))
+        );
+        assert_eq!(
+            parse_owner_signature(&format!("contract:{}:0xabcd", owner), hash),
+            Ok(OwnerSignature::contract(owner, vec![0xab, 0xcd].into()))
+        );
+        assert!(parse_owner_signature("contract:0xabcd", hash).is_err());
+        assert!(parse_owner_signature("0x1234", hash).is_err());
+    }
+
+    #[test]
+    fn test_exec_tx_mode() {
+        let mut args = base_args();
+        args[1] = "exec-tx".to_string();
+        args.extend(
+            [
+                "-u",
+                "1.3.0",
+                "--executor-nonce",
+                "3",
+                "--gas-limit",
+                "150000",
+                "--executor-gas-price",
+                "20gwei",
+            ]
+            .map(String::from),
+        );
+
+        let cli = CliArgs::try_parse_from(&args).unwrap();
+        if let Mode::ExecTx(exec_args) = cli.mode {
+            assert_eq!(exec_args.tx.nonce, 42);
+            assert_eq!(exec_args.executor_gas_price, Some(20_000_000_000));
+            assert_eq!(exec_args.max_fee_per_gas, None);
+        } else {
+            panic!("Expected ExecTx mode");
+        }
+
+        // EIP-1559 fees are required without a legacy gas price
+        let eip1559_args = &args[..args.len() - 2];
+        assert!(CliArgs::try_parse_from(eip1559_args).is_err());
+    }
+
+    #[test]
+    fn test_predict_mode() {
+        let args = ["safe-hash", "predict", "--chain", "zksync", "-u", "1.3.0"];
+        // Owners, threshold and singleton are required unless decoding calldata
+        assert!(CliArgs::try_parse_from(args).is_err());
+
+        let cli =
+            CliArgs::try_parse_from([&args[..], &["--calldata", "0x1688f0b9"]].concat()).unwrap();
+        if let Mode::Predict(predict_args) = cli.mode {
+            assert_eq!(predict_args.calldata.as_deref(), Some("0x1688f0b9"));
+            assert!(predict_args.owners.is_empty());
+        } else {
+            panic!("Expected Predict mode");
+        }
+
+        let conflicting = [&args[..], &["--calldata", "0x1688f0b9", "--threshold", "1"]].concat();
+        assert!(CliArgs::try_parse_from(conflicting).is_err());
+    }
+
+    #[test]
+    fn test_typed_mode() {
+        let args = ["safe-hash", "typed", "--file", "typed.json"];
+        // The Safe is required unless only the EIP-712 hash is computed
+        assert!(CliArgs::try_parse_from(args).is_err());
+        assert!(CliArgs::try_parse_from([&args[..], &["--standalone"]].concat()).is_ok());
+
+        let safe = [
+            "--chain",
+            "sepolia",
+            "--safe-address",
+            "0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1",
+            "--safe-version",
+            "1.4.1",
+        ];
+        let cli = CliArgs::try_parse_from([&["safe-hash", "typed"][..], &safe].concat()).unwrap();
+        if let Mode::Eip712(eip712_args) = cli.mode {
+            assert_eq!(eip712_args.file, None);
+            assert_eq!(eip712_args.chain.as_deref(), Some("sepolia"));
+            assert_eq!(eip712_args.safe_version, Some(SafeWalletVersion::new(1, 4, 1)));
+        } else {
+            panic!("Expected Eip712 mode");
+        }
+    }
+
+    #[test]
+    fn test_rpc_request_mode() {
+        let args = ["safe-hash", "rpc-request", "-", "--safe-version", "1.4.1", "--nonce", "3"];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        if let Mode::RpcRequest(rpc_args) = cli.mode {
+            assert_eq!(rpc_args.input, PathBuf::from("-"));
+            assert_eq!(rpc_args.chain, None);
+            assert_eq!(rpc_args.nonce, Some(3));
+        } else {
+            panic!("Expected RpcRequest mode");
+        }
+
+        // The Safe version cannot be read from the request
+        assert!(CliArgs::try_parse_from(["safe-hash", "rpc-request", "request.json"]).is_err());
+    }
+
+    #[test]
+    fn test_sign_mode() {
+        let args = [
+            "safe-hash",
+            "sign",
+            "--keystore",
+            "owner.json",
+            "msg",
+            "--chain",
+            "sepolia",
+            "--safe-address",
+            "0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1",
+            "--safe-version",
+            "1.3.0",
+            "--input-file",
+            "message.txt",
+            "--style",
+            "eth-sign",
+        ];
+
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.msg_args().map(|msg_args| msg_args.chain.as_str()), Some("sepolia"));
+        if let Mode::Sign(SignArgs { signer, .. }) = cli.mode {
+            assert_eq!(signer.keystore, Some(PathBuf::from("owner.json")));
+            assert_eq!(signer.style, SignatureStyle::EthSign);
+        } else {
+            panic!("Expected Sign mode");
+        }
+
+        let both_keys = ["safe-hash", "sign", "--keystore", "owner.json", "--mnemonic", "msg"];
+        assert!(CliArgs::try_parse_from(both_keys).is_err());
+    }
+
     #[test]
     fn test_message_mode() {
         let args = vec![
@@ -0,0 +1,106 @@
+use alloy::{hex, primitives::B256};
+use clap::ValueEnum;
+use std::fmt;
+
+/// Hardware wallets whose on-screen hash formatting we reproduce, so hashes can be compared
+/// character for character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Device {
+    /// Ledger blind signing: `0x` then upper-case hex, with the Safe tx hash as a binary string
+    /// literal
+    LedgerLegacy,
+    /// Ledger clear signing: `0x` then upper-case hex
+    LedgerClearSign,
+    /// Trezor: lower-case hex in groups of 4 characters
+    Trezor,
+    /// Keystone: `0x` then lower-case hex in groups of 8 characters
+    Keystone,
+    /// GridPlus Lattice: `0x` then upper-case hex in groups of 8 characters
+    Gridplus,
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Device::LedgerLegacy => write!(f, "Ledger (legacy)"),
+            Device::LedgerClearSign => write!(f, "Ledger (clear signing)"),
+            Device::Trezor => write!(f, "Trezor"),
+            Device::Keystone => write!(f, "Keystone"),
+            Device::Gridplus => write!(f, "GridPlus Lattice"),
+        }
+    }
+}
+
+impl Device {
+    /// Renders a hash the way the device shows it.
+    pub fn format_hash(&self, hash: &B256) -> String {
+        match self {
+            Device::LedgerLegacy | Device::LedgerClearSign => {
+                format!("0x{}", hex::encode_upper(hash))
+            }
+            Device::Trezor => group(&hex::encode(hash), 4),
+            Device::Keystone => format!("0x {}", group(&hex::encode(hash), 8)),
+            Device::Gridplus => format!("0x {}", group(&hex::encode_upper(hash), 8)),
+        }
+    }
+
+    /// Whether the device shows the hash as a binary string literal rather than hex.
+    pub fn shows_binary_literal(&self) -> bool {
+        matches!(self, Device::LedgerLegacy)
+    }
+}
+
+fn group(hex: &str, size: usize) -> String {
+    hex.as_bytes()
+        .chunks(size)
+        .map(|chunk| std::str::from_utf8(chunk).expect("hex is ASCII"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders the hash bytes as a string literal, printable ASCII as is and every other byte as
+/// `\xNN`, like legacy Ledger firmware does when blind signing.
+pub fn binary_literal(hash: &B256) -> String {
+    hash.iter()
+        .map(|byte| match byte {
+            0x20..=0x7e => (*byte as char).to_string(),
+            _ => format!("\\x{:02x}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::b256;
+
+    const HASH: B256 = b256!("0xad06b099fca34e51e4886643d95d9a19ace2cd024065efb66662a876e8c40343");
+
+    #[test]
+    fn test_format_hash() {
+        assert_eq!(
+            Device::LedgerClearSign.format_hash(&HASH),
+            "0xAD06B099FCA34E51E4886643D95D9A19ACE2CD024065EFB66662A876E8C40343"
+        );
+        assert_eq!(
+            Device::Trezor.format_hash(&HASH),
+            "ad06 b099 fca3 4e51 e488 6643 d95d 9a19 ace2 cd02 4065 efb6 6662 a876 e8c4 0343"
+        );
+        assert_eq!(
+            Device::Keystone.format_hash(&HASH),
+            "0x ad06b099 fca34e51 e4886643 d95d9a19 ace2cd02 4065efb6 6662a876 e8c40343"
+        );
+        assert_eq!(
+            Device::Gridplus.format_hash(&HASH),
+            "0x AD06B099 FCA34E51 E4886643 D95D9A19 ACE2CD02 4065EFB6 6662A876 E8C40343"
+        );
+    }
+
+    #[test]
+    fn test_binary_literal() {
+        assert_eq!(
+            binary_literal(&HASH),
+            r"\xad\x06\xb0\x99\xfc\xa3NQ\xe4\x88fC\xd9]\x9a\x19\xac\xe2\xcd\x02@e\xef\xb6fb\xa8v\xe8\xc4\x03C"
+        );
+    }
+}
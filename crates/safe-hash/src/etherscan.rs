@@ -1,20 +1,89 @@
-use alloy::primitives::ChainId;
-use reqwest::blocking::Client;
-use std::env;
+use alloy::primitives::{Address, ChainId};
+use clap::ValueEnum;
+use reqwest::{StatusCode, blocking::Client};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-pub fn is_contract_verfied(
-    address: &str,
-    chain_id: ChainId,
-) -> Result<bool, Box<dyn std::error::Error>> {
-    let api_key = env::var("ETHERSCAN_API_KEY")?;
-    let url = format!(
-        "https://api.etherscan.io/v2/api?chainid={}&module=contract&action=getsourcecode&address={}&apikey={}",
-        chain_id, address, api_key
-    );
+/// Unverified results are re-checked after this many seconds, verified ones are kept forever.
+const UNVERIFIED_CACHE_TTL: u64 = 24 * 60 * 60;
 
-    let client = Client::new();
-    let response = client.get(&url).send()?.json::<serde_json::Value>()?;
+const BLOCKSCOUT_INSTANCES: &[(ChainId, &str)] = &[
+    (1, "https://eth.blockscout.com"),
+    (10, "https://optimism.blockscout.com"),
+    (100, "https://gnosis.blockscout.com"),
+    (137, "https://polygon.blockscout.com"),
+    (324, "https://zksync.blockscout.com"),
+    (480, "https://worldchain-mainnet.explorer.alchemy.com"),
+    (1101, "https://zkevm.blockscout.com"),
+    (8453, "https://base.blockscout.com"),
+    (10200, "https://gnosis-chiado.blockscout.com"),
+    (42161, "https://arbitrum.blockscout.com"),
+    (42220, "https://celo.blockscout.com"),
+    (84532, "https://base-sepolia.blockscout.com"),
+    (534352, "https://scroll.blockscout.com"),
+    (11155111, "https://eth-sepolia.blockscout.com"),
+];
+
+/// Chains that are better covered by Sourcify and Blockscout than by Etherscan.
+const ETHERSCAN_POOR_CHAINS: &[ChainId] = &[196, 480, 10200];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VerifierKind {
+    Etherscan,
+    Sourcify,
+    Blockscout,
+}
+
+impl fmt::Display for VerifierKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifierKind::Etherscan => write!(f, "etherscan"),
+            VerifierKind::Sourcify => write!(f, "sourcify"),
+            VerifierKind::Blockscout => write!(f, "blockscout"),
+        }
+    }
+}
 
+/// Returns the providers to try, in order, for a chain when the user did not pick any.
+pub fn default_verifiers(chain_id: ChainId) -> Vec<VerifierKind> {
+    if ETHERSCAN_POOR_CHAINS.contains(&chain_id) {
+        vec![VerifierKind::Sourcify, VerifierKind::Blockscout]
+    } else {
+        vec![VerifierKind::Etherscan, VerifierKind::Sourcify, VerifierKind::Blockscout]
+    }
+}
+
+pub trait VerificationProvider {
+    fn kind(&self) -> VerifierKind;
+
+    fn is_contract_verified(
+        &self,
+        address: Address,
+        chain_id: ChainId,
+    ) -> Result<bool, Box<dyn std::error::Error>>;
+}
+
+pub struct Etherscan {
+    client: Client,
+}
+
+pub struct Sourcify {
+    client: Client,
+}
+
+pub struct Blockscout {
+    client: Client,
+}
+
+/// Parses an Etherscan style `getsourcecode` response, which Blockscout also serves.
+fn source_code_is_present(
+    response: &serde_json::Value,
+) -> Result<bool, Box<dyn std::error::Error>> {
     let results = response.get("result").ok_or("bad reponse")?.as_array().ok_or("bad response")?;
     let entry = results.first().ok_or("bad response")?;
 
@@ -24,29 +93,367 @@ pub fn is_contract_verfied(
     Ok(!source_code.is_empty())
 }
 
+impl VerificationProvider for Etherscan {
+    fn kind(&self) -> VerifierKind {
+        VerifierKind::Etherscan
+    }
+
+    fn is_contract_verified(
+        &self,
+        address: Address,
+        chain_id: ChainId,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let api_key = env::var("ETHERSCAN_API_KEY")?;
+        let url = format!(
+            "https://api.etherscan.io/v2/api?chainid={}&module=contract&action=getsourcecode&address={}&apikey={}",
+            chain_id, address, api_key
+        );
+
+        let response = self.client.get(&url).send()?.json::<serde_json::Value>()?;
+        source_code_is_present(&response)
+    }
+}
+
+impl VerificationProvider for Sourcify {
+    fn kind(&self) -> VerifierKind {
+        VerifierKind::Sourcify
+    }
+
+    fn is_contract_verified(
+        &self,
+        address: Address,
+        chain_id: ChainId,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let url = format!("https://sourcify.dev/server/v2/contract/{}/{}", chain_id, address);
+
+        let response = self.client.get(&url).send()?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        let response = response.error_for_status()?.json::<serde_json::Value>()?;
+
+        Ok(response.get("match").is_some_and(|m| !m.is_null()))
+    }
+}
+
+impl VerificationProvider for Blockscout {
+    fn kind(&self) -> VerifierKind {
+        VerifierKind::Blockscout
+    }
+
+    fn is_contract_verified(
+        &self,
+        address: Address,
+        chain_id: ChainId,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let instance = BLOCKSCOUT_INSTANCES
+            .iter()
+            .find(|(id, _)| *id == chain_id)
+            .map(|(_, url)| *url)
+            .ok_or_else(|| format!("no blockscout instance known for chain {chain_id}"))?;
+        let url =
+            format!("{}/api?module=contract&action=getsourcecode&address={}", instance, address);
+
+        let response = self.client.get(&url).send()?.json::<serde_json::Value>()?;
+        source_code_is_present(&response)
+    }
+}
+
+pub fn provider(kind: VerifierKind) -> Box<dyn VerificationProvider> {
+    let client = Client::new();
+    match kind {
+        VerifierKind::Etherscan => Box::new(Etherscan { client }),
+        VerifierKind::Sourcify => Box::new(Sourcify { client }),
+        VerifierKind::Blockscout => Box::new(Blockscout { client }),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    verified: bool,
+    provider: String,
+    checked_at: u64,
+}
+
+/// On-disk record of previous verification lookups, keyed by `<chain id>:<address>`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VerificationCache {
+    entries: HashMap<String, CacheEntry>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+fn cache_key(address: Address, chain_id: ChainId) -> String {
+    format!("{}:{}", chain_id, address.to_string().to_lowercase())
+}
+
+impl VerificationCache {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("safe-hash").join("verification.json"))
+    }
+
+    /// Loads the cache from `path`, starting empty if the file is missing or unreadable.
+    pub fn load(path: PathBuf) -> Self {
+        let mut cache: Self = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        cache.path = Some(path);
+        cache
+    }
+
+    pub fn get(&self, address: Address, chain_id: ChainId) -> Option<bool> {
+        let entry = self.entries.get(&cache_key(address, chain_id))?;
+        if !entry.verified && now().saturating_sub(entry.checked_at) > UNVERIFIED_CACHE_TTL {
+            return None;
+        }
+        Some(entry.verified)
+    }
+
+    pub fn insert(
+        &mut self,
+        address: Address,
+        chain_id: ChainId,
+        verified: bool,
+        provider: VerifierKind,
+    ) {
+        self.entries.insert(cache_key(address, chain_id), CacheEntry {
+            verified,
+            provider: provider.to_string(),
+            checked_at: now(),
+        });
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Checks contract verification against a fallback chain of providers.
+///
+/// Providers are asked in order until one reports the contract as verified. A provider that fails
+/// (missing API key, unsupported chain, network error) is skipped. The contract is reported as
+/// unverified only if at least one provider answered and none of them found it.
+pub struct ContractVerifier {
+    providers: Vec<Box<dyn VerificationProvider>>,
+    cache: Option<VerificationCache>,
+}
+
+impl ContractVerifier {
+    pub fn new(kinds: &[VerifierKind], cache: Option<VerificationCache>) -> Self {
+        Self::with_providers(kinds.iter().copied().map(provider).collect(), cache)
+    }
+
+    pub fn with_providers(
+        providers: Vec<Box<dyn VerificationProvider>>,
+        cache: Option<VerificationCache>,
+    ) -> Self {
+        Self { providers, cache }
+    }
+
+    pub fn is_contract_verified(
+        &mut self,
+        address: Address,
+        chain_id: ChainId,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if let Some(verified) = self.cache.as_ref().and_then(|cache| cache.get(address, chain_id)) {
+            return Ok(verified);
+        }
+
+        let mut answered = None;
+        let mut last_error: Option<Box<dyn std::error::Error>> = None;
+        for provider in &self.providers {
+            match provider.is_contract_verified(address, chain_id) {
+                Ok(true) => {
+                    answered = Some((true, provider.kind()));
+                    break;
+                }
+                Ok(false) => {
+                    answered.get_or_insert((false, provider.kind()));
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        match answered {
+            // A failing provider might have found it verified, so only a complete answer is cached
+            Some((false, _)) if last_error.is_some() => Ok(false),
+            Some((verified, kind)) => {
+                if let Some(cache) = &mut self.cache {
+                    cache.insert(address, chain_id, verified, kind);
+                    if let Err(err) = cache.save() {
+                        eprintln!("Warning: Could not write verification cache: {}", err);
+                    }
+                }
+                Ok(verified)
+            }
+            None => Err(last_error.unwrap_or_else(|| "no verification provider configured".into())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod check_contract_verification {
 
-    use std::convert::identity;
+    use std::{cell::Cell, convert::identity, rc::Rc};
 
     use super::*;
+    use alloy::primitives::address;
     use safe_utils::Of;
 
+    struct MockProvider {
+        kind: VerifierKind,
+        answer: Option<bool>,
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl VerificationProvider for MockProvider {
+        fn kind(&self) -> VerifierKind {
+            self.kind
+        }
+
+        fn is_contract_verified(
+            &self,
+            _address: Address,
+            _chain_id: ChainId,
+        ) -> Result<bool, Box<dyn std::error::Error>> {
+            self.calls.set(self.calls.get() + 1);
+            self.answer.ok_or_else(|| "provider unavailable".into())
+        }
+    }
+
+    fn mock(
+        kind: VerifierKind,
+        answer: Option<bool>,
+    ) -> (Box<dyn VerificationProvider>, Rc<Cell<u32>>) {
+        let calls = Rc::new(Cell::new(0));
+        (Box::new(MockProvider { kind, answer, calls: calls.clone() }), calls)
+    }
+
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+    #[test]
+    fn test_fallback_skips_failing_provider() {
+        let (failing, failing_calls) = mock(VerifierKind::Etherscan, None);
+        let (sourcify, sourcify_calls) = mock(VerifierKind::Sourcify, Some(true));
+        let mut verifier = ContractVerifier::with_providers(vec![failing, sourcify], None);
+
+        assert!(verifier.is_contract_verified(USDC, 1).unwrap());
+        assert_eq!(failing_calls.get(), 1);
+        assert_eq!(sourcify_calls.get(), 1);
+    }
+
+    #[test]
+    fn test_all_providers_failing_is_an_error() {
+        let (a, _) = mock(VerifierKind::Etherscan, None);
+        let (b, _) = mock(VerifierKind::Blockscout, None);
+        let mut verifier = ContractVerifier::with_providers(vec![a, b], None);
+
+        assert!(verifier.is_contract_verified(USDC, 1).is_err());
+    }
+
+    #[test]
+    fn test_unverified_only_when_no_provider_finds_it() {
+        let (a, _) = mock(VerifierKind::Etherscan, Some(false));
+        let (b, b_calls) = mock(VerifierKind::Sourcify, Some(true));
+        let mut verifier = ContractVerifier::with_providers(vec![a, b], None);
+        assert!(verifier.is_contract_verified(USDC, 1).unwrap());
+        assert_eq!(b_calls.get(), 1);
+
+        let (a, _) = mock(VerifierKind::Etherscan, Some(false));
+        let (b, _) = mock(VerifierKind::Sourcify, None);
+        let mut verifier = ContractVerifier::with_providers(vec![a, b], None);
+        assert!(!verifier.is_contract_verified(USDC, 1).unwrap());
+    }
+
+    #[test]
+    fn test_cache_is_used_and_persisted() {
+        let path =
+            env::temp_dir().join(format!("safe-hash-verification-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let (provider, calls) = mock(VerifierKind::Sourcify, Some(true));
+        let mut verifier = ContractVerifier::with_providers(
+            vec![provider],
+            Some(VerificationCache::load(path.clone())),
+        );
+        assert!(verifier.is_contract_verified(USDC, 1).unwrap());
+        assert!(verifier.is_contract_verified(USDC, 1).unwrap());
+        assert_eq!(calls.get(), 1);
+
+        let reloaded = VerificationCache::load(path.clone());
+        assert_eq!(reloaded.get(USDC, 1), Some(true));
+        assert_eq!(reloaded.get(USDC, 10), None);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_unverified_is_not_cached_when_a_provider_fails() {
+        let path = env::temp_dir()
+            .join(format!("safe-hash-verification-partial-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let (a, _) = mock(VerifierKind::Etherscan, Some(false));
+        let (b, b_calls) = mock(VerifierKind::Sourcify, None);
+        let mut verifier = ContractVerifier::with_providers(
+            vec![a, b],
+            Some(VerificationCache::load(path.clone())),
+        );
+        assert!(!verifier.is_contract_verified(USDC, 1).unwrap());
+        assert!(!verifier.is_contract_verified(USDC, 1).unwrap());
+        assert_eq!(b_calls.get(), 2);
+        assert_eq!(VerificationCache::load(path.clone()).get(USDC, 1), None);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stale_unverified_entries_expire() {
+        let mut cache = VerificationCache::default();
+        cache.insert(USDC, 1, false, VerifierKind::Etherscan);
+        assert_eq!(cache.get(USDC, 1), Some(false));
+
+        cache.entries.get_mut(&cache_key(USDC, 1)).unwrap().checked_at = 0;
+        assert_eq!(cache.get(USDC, 1), None);
+    }
+
+    #[test]
+    fn test_default_verifiers() {
+        assert_eq!(default_verifiers(1)[0], VerifierKind::Etherscan);
+        assert_eq!(default_verifiers(ChainId::of("xlayer").unwrap()), vec![
+            VerifierKind::Sourcify,
+            VerifierKind::Blockscout
+        ]);
+    }
+
     #[test]
     #[ignore = "repetitively running test can cause API Key to be blacklisted"]
     fn test_veridied_contracts() {
+        let etherscan = provider(VerifierKind::Etherscan);
         // DOGECOIN (Ethereum)
         assert!(
-            is_contract_verfied("0xc336f8408557272646d192628dc3f554b654b21a", 1)
+            etherscan
+                .is_contract_verified(address!("0xc336f8408557272646d192628dc3f554b654b21a"), 1)
                 .is_ok_and(identity)
         );
         // USDC (Arbitrum)
         assert!(
-            is_contract_verfied(
-                "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
-                ChainId::of("arbitrum").unwrap()
-            )
-            .is_ok_and(identity)
+            etherscan
+                .is_contract_verified(
+                    address!("0xaf88d065e77c8cC2239327C5EDb3A432268e5831"),
+                    ChainId::of("arbitrum").unwrap()
+                )
+                .is_ok_and(identity)
         );
     }
 }
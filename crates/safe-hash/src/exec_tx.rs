@@ -0,0 +1,163 @@
+use alloy::{
+    consensus::{SignableTransaction, TxEip1559, TxLegacy},
+    primitives::{
+        Address, B256, Bytes, ChainId, TxKind, U256,
+        utils::{ParseUnits, format_units, parse_units},
+    },
+};
+
+/// Parses a wei amount, or a gwei amount with a `gwei` suffix (e.g. `30gwei` or `1.5gwei`).
+pub fn parse_wei(value: &str) -> Result<u128, String> {
+    let invalid = || format!("{value:?} is not an amount in wei, or gwei with a `gwei` suffix");
+    let Some(gwei) = value.strip_suffix("gwei") else {
+        return value.parse().map_err(|_| invalid());
+    };
+    match parse_units(gwei.trim(), "gwei") {
+        Ok(ParseUnits::U256(wei)) => {
+            u128::try_from(wei).map_err(|_| format!("{value:?} does not fit in 128 bits"))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Formats wei as gwei without trailing zeros, e.g. `1.5 gwei`.
+pub fn format_gwei(wei: u128) -> String {
+    let gwei = format_units(wei, "gwei").unwrap_or_default();
+    format!("{} gwei", gwei.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Fee fields of the executor's transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutorFees {
+    Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+    Legacy { gas_price: u128 },
+}
+
+impl ExecutorFees {
+    /// The highest price per gas the executor may pay.
+    pub fn max_gas_price(&self) -> u128 {
+        match self {
+            ExecutorFees::Eip1559 { max_fee_per_gas, .. } => *max_fee_per_gas,
+            ExecutorFees::Legacy { gas_price } => *gas_price,
+        }
+    }
+}
+
+/// An unsigned transaction calling `execTransaction` on the Safe, for an offline signer.
+pub struct UnsignedExecutorTx {
+    /// Payload the executor signs: `0x02 || rlp(fields)` for EIP-1559, `rlp(fields)` with the
+    /// EIP-155 chain id for legacy transactions
+    pub encoded: Bytes,
+    pub signing_hash: B256,
+}
+
+pub fn build_executor_tx(
+    chain_id: ChainId,
+    nonce: u64,
+    gas_limit: u64,
+    fees: ExecutorFees,
+    safe_address: Address,
+    calldata: Bytes,
+) -> UnsignedExecutorTx {
+    let (encoded, signing_hash) = match fees {
+        ExecutorFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+            let tx = TxEip1559 {
+                chain_id,
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                to: TxKind::Call(safe_address),
+                value: U256::ZERO,
+                access_list: Default::default(),
+                input: calldata,
+            };
+            (tx.encoded_for_signing(), tx.signature_hash())
+        }
+        ExecutorFees::Legacy { gas_price } => {
+            let tx = TxLegacy {
+                chain_id: Some(chain_id),
+                nonce,
+                gas_price,
+                gas_limit,
+                to: TxKind::Call(safe_address),
+                value: U256::ZERO,
+                input: calldata,
+            };
+            (tx.encoded_for_signing(), tx.signature_hash())
+        }
+    };
+
+    UnsignedExecutorTx { encoded: encoded.into(), signing_hash }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        hex,
+        primitives::{address, keccak256},
+    };
+
+    const SAFE: Address = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+
+    #[test]
+    fn test_parse_wei() {
+        assert_eq!(parse_wei("21000"), Ok(21000));
+        assert_eq!(parse_wei("30gwei"), Ok(30_000_000_000));
+        assert_eq!(parse_wei("1.5gwei"), Ok(1_500_000_000));
+        assert!(parse_wei("1.5").is_err());
+        assert!(parse_wei("thirty").is_err());
+        assert_eq!(format_gwei(1_500_000_000), "1.5 gwei");
+        assert_eq!(format_gwei(30_000_000_000), "30 gwei");
+    }
+
+    #[test]
+    fn test_build_eip1559_executor_tx() {
+        let fees = ExecutorFees::Eip1559 {
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+        };
+        let tx = build_executor_tx(1, 7, 150_000, fees, SAFE, Bytes::from_static(&[0x6a, 0x76]));
+
+        assert_eq!(
+            hex::encode(&tx.encoded),
+            concat!(
+                "02",
+                "eb",
+                "01",
+                "07",
+                "843b9aca00",
+                "8506fc23ac00",
+                "830249f0",
+                "941c694fc3006d81ff4a56f97e1b99529066a23725",
+                "80",
+                "826a76",
+                "c0"
+            )
+        );
+        assert_eq!(tx.signing_hash, keccak256(&tx.encoded));
+    }
+
+    #[test]
+    fn test_build_legacy_executor_tx() {
+        let fees = ExecutorFees::Legacy { gas_price: 20_000_000_000 };
+        let tx = build_executor_tx(1, 0, 21_000, fees, SAFE, Bytes::new());
+
+        // EIP-155: chain id, 0, 0 appended to the six fields
+        assert_eq!(
+            hex::encode(&tx.encoded),
+            concat!(
+                "e4",
+                "80",
+                "8504a817c800",
+                "825208",
+                "941c694fc3006d81ff4a56f97e1b99529066a23725",
+                "80",
+                "80",
+                "018080"
+            )
+        );
+        assert_eq!(tx.signing_hash, keccak256(&tx.encoded));
+    }
+}
@@ -0,0 +1,335 @@
+use crate::tx_signing::TxInput;
+use alloy::{
+    dyn_abi::{DynSolValue, TypedData},
+    primitives::{Address, B256, ChainId, keccak256},
+    sol_types::Eip712Domain,
+};
+use safe_utils::SafeWalletVersion;
+use serde_json::json;
+
+/// One member of an `encodeData`, encoded as a 32-byte word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedMember {
+    pub name: String,
+    pub type_name: String,
+    /// The value itself for atomic types, the hash of its encoding otherwise
+    pub word: B256,
+}
+
+/// The `encodeData` of a struct or array, and its hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoding {
+    /// Where the value sits, e.g. `message.to[0]`
+    pub path: String,
+    /// `encodeType` of a struct, `None` for arrays, which are hashed without a typehash
+    pub encoded_type: Option<String>,
+    pub type_hash: Option<B256>,
+    pub members: Vec<EncodedMember>,
+    pub hash: B256,
+}
+
+/// Every step of an EIP-712 hash, down to each word that is hashed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip712Explanation {
+    /// Choices made while encoding that depend on the Safe version
+    pub notes: Vec<String>,
+    pub domain: Encoding,
+    /// The message struct, then every struct and array it contains, depth first
+    pub message: Vec<Encoding>,
+    pub domain_separator: B256,
+    pub struct_hash: B256,
+    /// `keccak256(0x1901 ‖ domain separator ‖ struct hash)`
+    pub digest: B256,
+}
+
+fn domain_encoding(domain: &Eip712Domain) -> Encoding {
+    let mut members = Vec::new();
+    let mut push = |name: &str, type_name: &str, word: B256| {
+        members.push(EncodedMember {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            word,
+        })
+    };
+    if let Some(name) = &domain.name {
+        push("name", "string", keccak256(name.as_bytes()));
+    }
+    if let Some(version) = &domain.version {
+        push("version", "string", keccak256(version.as_bytes()));
+    }
+    if let Some(chain_id) = domain.chain_id {
+        push("chainId", "uint256", chain_id.into());
+    }
+    if let Some(verifying_contract) = domain.verifying_contract {
+        push("verifyingContract", "address", verifying_contract.into_word());
+    }
+    if let Some(salt) = domain.salt {
+        push("salt", "bytes32", salt);
+    }
+
+    Encoding {
+        path: "domain".to_string(),
+        encoded_type: Some(domain.encode_type()),
+        type_hash: Some(domain.type_hash()),
+        members,
+        hash: domain.hash_struct(),
+    }
+}
+
+/// Type names of the members of a struct, from its definition in the typed data.
+fn member_types(typed_data: &TypedData, name: &str) -> Result<Vec<String>, String> {
+    let types = typed_data.resolver.linearize(name).map_err(|e| e.to_string())?;
+    let type_def = types
+        .into_iter()
+        .find(|type_def| type_def.type_name() == name)
+        .ok_or_else(|| format!("type {} is not defined", name))?;
+    Ok(type_def.prop_types().map(str::to_string).collect())
+}
+
+/// Appends the encoding of `value` and of every struct and array inside it, parent first.
+fn push_encodings(
+    typed_data: &TypedData,
+    path: String,
+    type_name: &str,
+    value: &DynSolValue,
+    encodings: &mut Vec<Encoding>,
+) -> Result<(), String> {
+    let resolver = &typed_data.resolver;
+    let word = |value: &DynSolValue| resolver.eip712_data_word(value).map_err(|e| e.to_string());
+
+    let (encoded_type, type_hash, children) = match value {
+        DynSolValue::CustomStruct { name, prop_names, tuple } => {
+            let types = member_types(typed_data, name)?;
+            let children = prop_names
+                .iter()
+                .zip(types)
+                .zip(tuple)
+                .map(|((name, type_name), value)| (name.clone(), type_name, value))
+                .collect::<Vec<_>>();
+            let encoded_type = resolver.encode_type(name).map_err(|e| e.to_string())?;
+            let type_hash = resolver.type_hash(name).map_err(|e| e.to_string())?;
+            (Some(encoded_type), Some(type_hash), children)
+        }
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            let element_type = type_name.rsplit_once('[').map_or(type_name, |(element, _)| element);
+            let children = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (format!("[{}]", i), element_type.to_string(), value))
+                .collect();
+            (None, None, children)
+        }
+        _ => return Ok(()),
+    };
+
+    let members = children
+        .iter()
+        .map(|(name, type_name, value)| {
+            Ok(EncodedMember {
+                name: name.clone(),
+                type_name: type_name.clone(),
+                word: word(value)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    encodings.push(Encoding {
+        path: path.clone(),
+        encoded_type,
+        type_hash,
+        members,
+        hash: word(value)?,
+    });
+
+    for (name, type_name, value) in children {
+        let child_path = if name.starts_with('[') {
+            format!("{}{}", path, name)
+        } else {
+            format!("{}.{}", path, name)
+        };
+        push_encodings(typed_data, child_path, &type_name, value, encodings)?;
+    }
+    Ok(())
+}
+
+/// Breaks down the EIP-712 hash of typed data.
+pub fn explain_typed_data(typed_data: &TypedData) -> Result<Eip712Explanation, String> {
+    let value = typed_data.coerce().map_err(|e| e.to_string())?;
+    let mut message = Vec::new();
+    push_encodings(
+        typed_data,
+        "message".to_string(),
+        &typed_data.primary_type,
+        &value,
+        &mut message,
+    )?;
+
+    let domain = domain_encoding(&typed_data.domain);
+    let struct_hash = typed_data.hash_struct().map_err(|e| e.to_string())?;
+    let digest = typed_data.eip712_signing_hash().map_err(|e| e.to_string())?;
+    Ok(Eip712Explanation {
+        notes: Vec::new(),
+        domain_separator: domain.hash,
+        domain,
+        message,
+        struct_hash,
+        digest,
+    })
+}
+
+/// Breaks down the hash of a Safe transaction, using the domain and `SafeTx` type that
+/// `DomainHasher` and `TxMessageHasher` choose for the Safe version.
+pub fn explain_safe_tx(
+    tx: &TxInput,
+    safe_address: Address,
+    nonce: u64,
+    chain_id: ChainId,
+    safe_version: &SafeWalletVersion,
+) -> Result<Eip712Explanation, String> {
+    let mut notes = Vec::new();
+    let (domain_types, domain) = if *safe_version >= SafeWalletVersion::new(1, 3, 0) {
+        notes.push(format!(
+            "Safe {} is 1.3.0 or later: the domain includes the chain id",
+            safe_version
+        ));
+        (
+            json!([
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ]),
+            json!({"chainId": chain_id, "verifyingContract": safe_address}),
+        )
+    } else {
+        notes.push(format!(
+            "Safe {} is older than 1.3.0: the domain has no chainId, only the verifyingContract",
+            safe_version
+        ));
+        (
+            json!([{"name": "verifyingContract", "type": "address"}]),
+            json!({"verifyingContract": safe_address}),
+        )
+    };
+    let base_gas = if *safe_version >= SafeWalletVersion::new(1, 0, 0) {
+        notes.push(format!("Safe {} is 1.0.0 or later: SafeTx has baseGas", safe_version));
+        "baseGas"
+    } else {
+        notes.push(format!(
+            "Safe {} is older than 1.0.0: SafeTx names baseGas dataGas, which changes the typehash",
+            safe_version
+        ));
+        "dataGas"
+    };
+
+    let typed_data: TypedData = serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": domain_types,
+            "SafeTx": [
+                {"name": "to", "type": "address"},
+                {"name": "value", "type": "uint256"},
+                {"name": "data", "type": "bytes"},
+                {"name": "operation", "type": "uint8"},
+                {"name": "safeTxGas", "type": "uint256"},
+                {"name": base_gas, "type": "uint256"},
+                {"name": "gasPrice", "type": "uint256"},
+                {"name": "gasToken", "type": "address"},
+                {"name": "refundReceiver", "type": "address"},
+                {"name": "nonce", "type": "uint256"}
+            ]
+        },
+        "primaryType": "SafeTx",
+        "domain": domain,
+        "message": {
+            "to": tx.to,
+            "value": tx.value,
+            "data": tx.data,
+            "operation": tx.operation,
+            "safeTxGas": tx.safe_tx_gas,
+            base_gas: tx.base_gas(),
+            "gasPrice": tx.gas_price,
+            "gasToken": tx.gas_token,
+            "refundReceiver": tx.refund_receiver,
+            "nonce": nonce
+        }
+    }))
+    .map_err(|e| e.to_string())?;
+
+    let mut explanation = explain_typed_data(&typed_data)?;
+    explanation.notes = notes;
+    Ok(explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx_signing::tx_signing_hashes;
+    use alloy::primitives::{U256, address};
+
+    fn tx() -> TxInput {
+        TxInput::new(
+            address!("0xdd13E55209Fd76AfE204dBda4007C227904f0a81"),
+            U256::ZERO,
+            "0xa9059cbb00000000000000000000000036bffa3048d89fad48509c83fdb6a3410232f3d300000000000000000000000000000000000000000000000000038d7ea4c68000".to_string(),
+            0,
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            Address::ZERO,
+            Address::ZERO,
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_explain_typed_data() {
+        let message = std::fs::read_to_string("../../test/eip712_message.json").unwrap();
+        let typed_data: TypedData = serde_json::from_str(&message).unwrap();
+        let explanation = explain_typed_data(&typed_data).unwrap();
+
+        assert_eq!(explanation.digest, typed_data.eip712_signing_hash().unwrap());
+        assert_eq!(explanation.domain_separator, typed_data.domain.separator());
+        assert_eq!(explanation.domain.members.len(), 4);
+
+        let paths = explanation.message.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, [
+            "message",
+            "message.from",
+            "message.from.wallets",
+            "message.to",
+            "message.to[0]",
+            "message.to[0].wallets",
+        ]);
+        let root = &explanation.message[0];
+        assert_eq!(root.hash, explanation.struct_hash);
+        assert_eq!(
+            root.encoded_type.as_deref(),
+            Some(
+                "Mail(Person from,Person[] to,string contents)Person(string name,address[] wallets)"
+            )
+        );
+        assert_eq!(root.members[0].word, explanation.message[1].hash);
+        assert_eq!(root.members[1].word, explanation.message[3].hash);
+        assert_eq!(explanation.message[3].type_hash, None);
+        assert_eq!(explanation.message[3].members[0].type_name, "Person");
+    }
+
+    #[test]
+    fn test_explain_safe_tx() {
+        let safe = address!("0xbC7977C6694Ae2Ae8Ad96bb1C100a281D928b7DB");
+        for (major, minor) in [(1, 4), (1, 1), (0, 1)] {
+            let version = SafeWalletVersion::new(major, minor, 0);
+            let explanation = explain_safe_tx(&tx(), safe, 7, 11155111, &version).unwrap();
+            let hashes = tx_signing_hashes(&tx(), safe, 7, 11155111, version.clone());
+
+            assert_eq!(explanation.domain_separator, hashes.domain_hash);
+            assert_eq!(explanation.struct_hash, hashes.message_hash);
+            assert_eq!(explanation.digest, hashes.safe_tx_hash);
+        }
+
+        let old = explain_safe_tx(&tx(), safe, 7, 1, &SafeWalletVersion::new(0, 1, 0)).unwrap();
+        assert_eq!(old.notes.len(), 2);
+        assert_eq!(
+            old.domain.encoded_type.as_deref(),
+            Some("EIP712Domain(address verifyingContract)")
+        );
+        assert!(old.message[0].encoded_type.as_ref().unwrap().contains("uint256 dataGas"));
+    }
+}
@@ -1,235 +1,1144 @@
+mod addressbook;
 mod api;
 mod cli;
+mod device;
 mod etherscan;
+mod exec_tx;
+mod explain;
+mod module;
 mod msg_signing;
+mod multisend;
+mod nested;
+mod onchain;
 mod output;
+mod predict;
+mod rpc;
+mod signer;
+mod simulate;
+mod siwe;
+mod tokens;
 mod tx_signing;
+mod typed_data;
 mod warn;
 
+use addressbook::AddressBook;
 use alloy::{
     hex::{self},
-    primitives::{Address, B256, ChainId, U256, keccak256},
+    primitives::{Address, B256, Bytes, ChainId, U256, keccak256},
 };
 use clap::Parser;
-use cli::{CliArgs, Eip712Args, Mode};
+use cli::{
+    AddressBookAction, CliArgs, Eip712Args, MessageArgs, Mode, ModuleArgs, PredictArgs,
+    RpcRequestArgs, SignArgs, SignTarget, SignaturesAction, SignaturesArgs, TransactionArgs,
+    parse_owner_signature,
+};
+use etherscan::{ContractVerifier, VerificationCache, default_verifiers};
+use exec_tx::{ExecutorFees, build_executor_tx};
+use explain::{explain_safe_tx, explain_typed_data};
+use module::{
+    ModuleCall, ModuleEntryPoint, allowance_transfer_hash, decode_module_call,
+    delay_transaction_hash,
+};
 use msg_signing::*;
+use multisend::{Call, flatten_calls};
+use nested::{nested_levels, nested_signing_hashes};
+use onchain::{OnchainSafe, RpcClient, check_onchain_safe, read_onchain_safe};
 use output::{
-    SafeWarnings, display_api_transaction_details, display_eip712_hash, display_full_tx,
-    display_hashes, display_message_hashes, display_safe_ui_values_for_eip712, display_warnings,
+    SafeHashes, SafeWarnings, TypedDataIssue, display_allowance_transfer,
+    display_api_transaction_details, display_combined_signatures, display_contract_signature_check,
+    display_eip712_explanation, display_eip712_hash, display_executor_tx, display_full_tx,
+    display_hashes, display_message_bytes, display_message_hashes, display_module_transaction,
+    display_multisend_calls, display_nested_hashes, display_onchain_safe,
+    display_predicted_address, display_proxy_creation, display_safe_owners,
+    display_safe_ui_values_for_eip712, display_signature, display_simulation, display_siwe_message,
+    display_token_call, display_typed_data_summary, display_warnings,
+};
+use predict::{ProxyCreation, SafeSetup, ZKSYNC_CHAIN_ID, proxy_address, zksync_proxy_address};
+use rpc::{RpcRequest, SigningRequest, parse_rpc_request};
+use safe_utils::{
+    CheckedHash, DomainHasher, Eip712Hasher, FullTx, MessageHasher, Of, OwnerSignature,
+    SafeWalletVersion, SignatureKind, get_chain_name, pack_signatures, unpack_signatures,
+    verify_contract_signature,
 };
-use safe_utils::{DomainHasher, Eip712Hasher, FullTx, MessageHasher, Of};
-use std::fs;
+use signer::{load_signer, sign_safe_hash};
+use simulate::{check_simulation, simulate_on_rpc};
+use siwe::{SiweMessage, check_siwe_message};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tokens::{TokenCall, TokenRegistry};
 use tx_signing::*;
-use warn::check_suspicious_content;
+use typed_data::{
+    SafeTypedData, TypedDataInput, check_domain, check_typed_data, decode_safe_typed_data,
+    parse_typed_data_input, safe_typed_data_version, summarize_typed_data,
+};
+use warn::{
+    check_address_poisoning, check_approvals, check_counterparties, check_nonce,
+    check_safe_configuration, check_suspicious_content, check_token_call, transaction_addresses,
+};
 
 fn main() {
     let args = CliArgs::parse();
     args.validate_safe_version();
     args.validate_chain();
     args.validate_to_for_offline();
-    args.validate_eip712_args();
+    args.validate_safe_address();
+    args.validate_signer();
+    args.validate_nested_safes();
 
     match args.mode {
         Mode::Transaction(tx_args) => {
-            let chain_id = ChainId::of(&tx_args.chain).unwrap_or_else(|_| {
-                panic!("chain {:?} is supported but id is not found", tx_args.chain)
+            run_transaction(&tx_args);
+        }
+        Mode::AddressBook(book_args) => {
+            let path = AddressBook::resolve_path(book_args.address_book).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            let mut book = AddressBook::load(&path).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
             });
+            let chain_id_of = |chain: &str| {
+                ChainId::of(chain).unwrap_or_else(|_| {
+                    eprintln!("chain {:?} is not supported", chain);
+                    std::process::exit(1);
+                })
+            };
 
-            // Try to get transaction details from API
-            let api_tx = if tx_args.offline {
-                Ok(None)
-            } else {
-                match api::get_safe_transaction(
-                    chain_id,
-                    tx_args.safe_address,
-                    tx_args.nonce as u64,
-                ) {
-                    Ok(tx) => Ok(Some(tx)),
-                    Err(e) => {
-                        eprintln!("Warning: Could not fetch transaction from API: {}", e);
-                        eprintln!("Falling back to offline mode with provided parameters");
-                        Err(e)
+            match book_args.action {
+                AddressBookAction::Add { chain, address, label } => {
+                    match book.insert(chain_id_of(&chain), address, label.clone()) {
+                        Some(previous) => {
+                            println!("Relabeled {} from {:?} to {:?}", address, previous, label)
+                        }
+                        None => println!("Added {} as {:?}", address, label),
                     }
                 }
-            };
-
-            let mut warnings = SafeWarnings::new();
-            let tx_data = if let Ok(Some(api_tx)) = &api_tx {
-                // Validate that user-provided details match API data if any were provided
-                if let Err(errors) = api::validate_transaction_details(api_tx, &tx_args) {
-                    if !errors.is_empty() {
-                        warnings.argument_mismatches.extend(errors);
-                        display_warnings(&warnings);
-                        return;
+                AddressBookAction::Remove { chain, address } => {
+                    match book.remove(chain_id_of(&chain), address) {
+                        Some(label) => println!("Removed {} ({})", address, label),
+                        None => {
+                            eprintln!("{} is not in the address book for {}", address, chain);
+                            std::process::exit(1);
+                        }
                     }
                 }
+                AddressBookAction::List { chain } => {
+                    let chain_id = chain.as_deref().map(chain_id_of);
+                    for (entry_chain_id, address, label) in book.entries() {
+                        if chain_id.is_none_or(|chain_id| chain_id == entry_chain_id) {
+                            println!("{:<10} {} {}", entry_chain_id, address, label);
+                        }
+                    }
+                    return;
+                }
+            }
 
-                // Use API data for transaction
-                TxInput::new(
-                    api_tx.to,
-                    U256::from_str_radix(&api_tx.value, 10).unwrap_or(U256::ZERO),
-                    api_tx.data.clone(),
-                    api_tx.operation,
-                    U256::from(api_tx.safe_tx_gas),
-                    U256::from(api_tx.base_gas),
-                    U256::from_str_radix(&api_tx.gas_price, 10).unwrap_or(U256::ZERO),
-                    api_tx.gas_token,
-                    api_tx.refund_receiver,
-                    api_tx.signatures.clone().unwrap_or(String::new()),
-                )
-            } else {
-                // Use user-provided data for transaction
-                TxInput::new(
-                    tx_args.to.expect("--to not found"),
-                    tx_args.value,
-                    tx_args.data.clone(),
-                    tx_args.operation,
-                    tx_args.safe_tx_gas,
-                    tx_args.base_gas,
-                    tx_args.gas_price,
-                    tx_args.gas_token,
-                    tx_args.refund_receiver,
-                    String::new(), // No signatures needed for signing
-                )
+            book.save(&path).unwrap_or_else(|e| {
+                eprintln!("Failed to write address book {}: {}", path.display(), e);
+                std::process::exit(1);
+            });
+        }
+        Mode::Message(msg_args) => {
+            run_message(&msg_args);
+        }
+        Mode::Eip712(eip712_args) => {
+            run_eip712(eip712_args);
+        }
+        Mode::Sign(sign_args) => {
+            let SignArgs { signer: signer_args, sign_despite_warnings, target } = sign_args;
+            let (hash, blocking_warnings) = match target {
+                SignTarget::Transaction(tx_args) => match run_transaction(&tx_args) {
+                    Some(target) => (target.hashes.safe_tx_hash, target.blocking_warnings),
+                    None => {
+                        eprintln!("Refusing to sign a transaction that does not match the API");
+                        std::process::exit(1);
+                    }
+                },
+                SignTarget::Message(msg_args) => {
+                    let (hashes, warnings) = run_message(&msg_args);
+                    (hashes.safe_tx_hash, warnings.blocks_signing())
+                }
+                SignTarget::Eip712(eip712_args) => {
+                    let (hash, warnings) = run_eip712(eip712_args);
+                    (hash, warnings.blocks_signing())
+                }
             };
-
-            if let Ok(Some(api_tx)) = &api_tx {
-                let full_tx = FullTx::new(
-                    api_tx.to,
-                    U256::from_str_radix(&api_tx.value, 10).unwrap_or(U256::ZERO),
-                    api_tx.data.clone(),
-                    api_tx.operation,
-                    U256::from(api_tx.safe_tx_gas),
-                    U256::from(api_tx.base_gas),
-                    U256::from_str_radix(&api_tx.gas_price, 10).unwrap_or(U256::ZERO),
-                    api_tx.gas_token,
-                    api_tx.refund_receiver,
-                    U256::from(api_tx.nonce),
-                    api_tx.signatures.clone().unwrap_or(String::new()),
+            if blocking_warnings && !sign_despite_warnings {
+                eprintln!(
+                    "Refusing to sign despite the warnings above, pass --sign-despite-warnings to override"
                 );
-                display_full_tx(full_tx.calldata(), full_tx.calldata_hash().unwrap_or_default());
+                std::process::exit(1);
             }
-            // Calculate hashes
-            let hashes = tx_signing_hashes(
-                &tx_data,
-                tx_args.safe_address,
-                tx_args.nonce,
+
+            let signer = load_signer(&signer_args).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            // A mnemonic passphrase derives a different account, so show which one signs
+            println!("\n{:<24} {} ({})", "Signing As:", signer.address(), signer_args.hd_path);
+            let signature = sign_safe_hash(&signer, hash, signer_args.style).unwrap_or_else(|e| {
+                eprintln!("Failed to sign: {}", e);
+                std::process::exit(1);
+            });
+            display_signature(signer.address(), hash, signer_args.style, &signature);
+        }
+        Mode::Signatures(SignaturesArgs { action: SignaturesAction::Combine(combine_args) }) => {
+            let Some(target) = run_transaction(&combine_args.tx) else {
+                eprintln!(
+                    "Refusing to combine signatures for a transaction that does not match the API"
+                );
+                std::process::exit(1);
+            };
+            let (signatures, packed) = combine_signatures(&target, &combine_args.signatures);
+            let calldata = exec_transaction_calldata(&target, &packed);
+            display_combined_signatures(
+                &signatures,
+                target.threshold,
+                &packed,
+                target.safe_address,
+                &calldata,
+            );
+        }
+        Mode::ExecTx(exec_args) => {
+            let Some(target) = run_transaction(&exec_args.tx) else {
+                eprintln!(
+                    "Refusing to build the execution of a transaction that does not match the API"
+                );
+                std::process::exit(1);
+            };
+            let chain_id = ChainId::of(&exec_args.tx.chain).unwrap_or_else(|_| {
+                panic!("chain {:?} is supported but id is not found", exec_args.tx.chain)
+            });
+
+            let (signatures, packed) = combine_signatures(&target, &exec_args.signatures);
+            let calldata = exec_transaction_calldata(&target, &packed);
+            let fees = match exec_args.executor_gas_price {
+                Some(gas_price) => ExecutorFees::Legacy { gas_price },
+                None => ExecutorFees::Eip1559 {
+                    max_fee_per_gas: exec_args
+                        .max_fee_per_gas
+                        .expect("--max-fee-per-gas not provided"),
+                    max_priority_fee_per_gas: exec_args
+                        .max_priority_fee_per_gas
+                        .expect("--max-priority-fee-per-gas not provided"),
+                },
+            };
+            let unsigned = build_executor_tx(
+                chain_id,
+                exec_args.executor_nonce,
+                exec_args.gas_limit,
+                fees,
+                target.safe_address,
+                hex::decode(&calldata).expect("calldata is hex").into(),
+            );
+            display_executor_tx(
+                &target,
+                &signatures,
                 chain_id,
-                tx_args.safe_version.clone(),
+                exec_args.executor_nonce,
+                exec_args.gas_limit,
+                fees,
+                &unsigned,
             );
+        }
+        Mode::Module(module_args) => {
+            run_module(&module_args);
+        }
+        Mode::Predict(predict_args) => {
+            run_predict(&predict_args);
+        }
+        Mode::RpcRequest(rpc_args) => {
+            run_rpc_request(&rpc_args);
+        }
+    }
+}
 
-            let nested_tx_data: Option<TxInput> =
-                match (tx_args.nested_safe_address, tx_args.nested_safe_nonce) {
-                    (Some(_nested_safe_address), Some(_)) => {
-                        let data = format!("0xd4d9bdcd{}", hex::encode(hashes.safe_tx_hash));
-                        Some(TxInput::new(
-                            tx_args.safe_address,
-                            U256::ZERO,
-                            data,
-                            0,
-                            U256::ZERO,
-                            U256::ZERO,
-                            U256::ZERO,
-                            Address::ZERO,
-                            Address::ZERO,
-                            String::new(),
-                        ))
-                    }
-                    (_, _) => None,
-                };
+/// Loads the address book at `path`, or the default one, which may not exist yet.
+fn load_address_book(path: &Option<PathBuf>) -> AddressBook {
+    path.clone()
+        .or_else(AddressBook::default_path)
+        .map(|path| AddressBook::load(&path))
+        .transpose()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        })
+        .unwrap_or_default()
+}
 
-            // Validate Safe Transaction Hash against API data if available
-            if let Ok(Some(api_tx)) = &api_tx {
-                // Display API transaction details
-                display_api_transaction_details(api_tx);
+/// Merges the API confirmations of the target with the `--signature` inputs, which take
+/// precedence for the same owner, and packs them.
+fn combine_signatures(target: &SigningTarget, inputs: &[String]) -> (Vec<OwnerSignature>, Bytes) {
+    let safe_tx_hash = target.hashes.safe_tx_hash;
+
+    let mut signatures = Vec::new();
+    for confirmation in &target.confirmations {
+        let unpacked = hex::decode(confirmation)
+            .map_err(|e| e.to_string())
+            .and_then(|packed| unpack_signatures(&packed, safe_tx_hash).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                eprintln!("Invalid confirmation from the API {}: {}", confirmation, e);
+                std::process::exit(1);
+            });
+        signatures.extend(unpacked);
+    }
+    for input in inputs {
+        let signature = parse_owner_signature(input, safe_tx_hash).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        // A provided signature replaces the API confirmation of the same owner
+        signatures.retain(|existing: &OwnerSignature| existing.owner != signature.owner);
+        signatures.push(signature);
+    }
+    signatures.sort_by_key(|signature| signature.owner);
 
-                let dangerous_methods =
-                    ["addOwnerWithThreshold", "removeOwner", "swapOwner", "changeThreshold"];
+    let packed = pack_signatures(signatures.clone()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    (signatures, packed)
+}
 
-                if let Some(decoded) = &api_tx.data_decoded {
-                    if dangerous_methods.iter().any(|m| *m == decoded.method) {
-                        warnings.dangerous_methods = true;
-                    }
-                }
+/// Verifies the EIP-1271 signatures of owner Safes against their owners and threshold from the
+/// API, printing each verification.
+fn verify_contract_signatures(
+    signatures: &[OwnerSignature],
+    checked: &CheckedHash,
+    chain_id: ChainId,
+    address_book: &AddressBook,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    let lookup = |safe: Address| {
+        api::get_safe_info(chain_id, safe).ok().and_then(|safe_info| safe_info.ownership())
+    };
 
-                if let Err(e) = api::validate_safe_tx_hash(api_tx, &hashes.safe_tx_hash) {
-                    warnings.argument_mismatches.push(e);
+    for signature in signatures {
+        let SignatureKind::Contract(data) = &signature.kind else { continue };
+        match verify_contract_signature(signature.owner, data, checked, chain_id, &lookup) {
+            Ok(check) => {
+                display_contract_signature_check(&check, address_book, chain_id);
+                if !check.accepted {
+                    warnings.rejected_contract_signatures.push(signature.owner);
                 }
             }
+            Err(e) => eprintln!(
+                "Warning: Could not verify the contract signature of {}: {}",
+                signature.owner, e
+            ),
+        }
+    }
 
-            // Check for suspicious content and union warnings
-            warnings.union(check_suspicious_content(&tx_data, Some(chain_id)));
+    warnings
+}
 
-            // Display hashes
-            println!("\nMain transaction");
-            display_hashes(&hashes);
+/// Hex `execTransaction` calldata of the target with the packed signatures.
+fn exec_transaction_calldata(target: &SigningTarget, packed: &[u8]) -> String {
+    let Some(tx_data) = &target.tx_data else {
+        eprintln!(
+            "The nested Safe approves with a SafeMessage, there is no transaction to execute"
+        );
+        std::process::exit(1);
+    };
+    FullTx::new(
+        tx_data.to,
+        tx_data.value,
+        tx_data.data.clone(),
+        tx_data.operation,
+        tx_data.safe_tx_gas,
+        tx_data.base_gas(),
+        tx_data.gas_price,
+        tx_data.gas_token,
+        tx_data.refund_receiver,
+        U256::from(target.nonce),
+        hex::encode(packed),
+    )
+    .calldata()
+}
 
-            // Calculate nested hashes
-            if let Some(nested_tx_data) = nested_tx_data {
-                let nhashes = tx_signing_hashes(
-                    &nested_tx_data,
-                    tx_args.nested_safe_address.expect("--nested-safe-address not provided"),
-                    tx_args.nested_safe_nonce.expect("--nested-safe-none not provided"),
-                    chain_id,
-                    tx_args.safe_version.clone(),
+/// Verifies a transaction and prints its hashes and warnings. Returns the transaction owners sign,
+/// the nested Safe's approval when one is given, or `None` when the arguments do not match the
+/// API.
+fn run_transaction(tx_args: &TransactionArgs) -> Option<SigningTarget> {
+    let chain_id = ChainId::of(&tx_args.chain)
+        .unwrap_or_else(|_| panic!("chain {:?} is supported but id is not found", tx_args.chain));
+
+    let tokens = TokenRegistry::load(chain_id, &tx_args.token_list).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let address_book = load_address_book(&tx_args.address_book);
+    // Try to get transaction details from API
+    let api_tx = if tx_args.offline {
+        Ok(None)
+    } else {
+        match api::get_safe_transaction(chain_id, tx_args.safe_address, tx_args.nonce) {
+            Ok(tx) => Ok(Some(tx)),
+            Err(e) => {
+                eprintln!("Warning: Could not fetch transaction from API: {}", e);
+                eprintln!("Falling back to offline mode with provided parameters");
+                Err(e)
+            }
+        }
+    };
+
+    let safe_info = match &api_tx {
+        Ok(Some(_)) => api::get_safe_info(chain_id, tx_args.safe_address)
+            .map_err(|e| eprintln!("Warning: Could not fetch Safe info from API: {}", e))
+            .ok(),
+        _ => None,
+    };
+
+    let mut warnings = SafeWarnings::new();
+    let tx_data = if let Ok(Some(api_tx)) = &api_tx {
+        // Validate that user-provided details match API data if any were provided
+        if let Err(errors) = api::validate_transaction_details(api_tx, tx_args) {
+            if !errors.is_empty() {
+                warnings.argument_mismatches.extend(errors);
+                display_warnings(&warnings);
+                return None;
+            }
+        }
+
+        // Use API data for transaction
+        TxInput::new(
+            api_tx.to,
+            U256::from_str_radix(&api_tx.value, 10).unwrap_or(U256::ZERO),
+            api_tx.data.clone(),
+            api_tx.operation,
+            U256::from(api_tx.safe_tx_gas),
+            U256::from(api_tx.base_gas),
+            U256::from_str_radix(&api_tx.gas_price, 10).unwrap_or(U256::ZERO),
+            api_tx.gas_token,
+            api_tx.refund_receiver,
+            api_tx.signatures.clone().unwrap_or(String::new()),
+        )
+    } else {
+        // Use user-provided data for transaction
+        TxInput::new(
+            tx_args.to.expect("--to not found"),
+            tx_args.value,
+            tx_args.data.clone(),
+            tx_args.operation,
+            tx_args.safe_tx_gas,
+            tx_args.base_gas,
+            tx_args.gas_price,
+            tx_args.gas_token,
+            tx_args.refund_receiver,
+            String::new(), // No signatures needed for signing
+        )
+    };
+
+    if let Ok(Some(api_tx)) = &api_tx {
+        let full_tx = FullTx::new(
+            api_tx.to,
+            U256::from_str_radix(&api_tx.value, 10).unwrap_or(U256::ZERO),
+            api_tx.data.clone(),
+            api_tx.operation,
+            U256::from(api_tx.safe_tx_gas),
+            U256::from(api_tx.base_gas),
+            U256::from_str_radix(&api_tx.gas_price, 10).unwrap_or(U256::ZERO),
+            api_tx.gas_token,
+            api_tx.refund_receiver,
+            U256::from(api_tx.nonce),
+            api_tx.signatures.clone().unwrap_or(String::new()),
+        );
+        display_full_tx(full_tx.calldata(), full_tx.calldata_hash().unwrap_or_default());
+    }
+    // Calculate hashes
+    let hashes = tx_signing_hashes(
+        &tx_data,
+        tx_args.safe_address,
+        tx_args.nonce,
+        chain_id,
+        tx_args.safe_version.clone(),
+    );
+
+    // Validate Safe Transaction Hash against API data if available
+    if let Ok(Some(api_tx)) = &api_tx {
+        // Display API transaction details
+        display_api_transaction_details(api_tx, &address_book, chain_id);
+        if let Some(safe_info) = &safe_info {
+            display_safe_owners(safe_info, &address_book, chain_id);
+        }
+
+        let dangerous_methods =
+            ["addOwnerWithThreshold", "removeOwner", "swapOwner", "changeThreshold"];
+
+        if let Some(decoded) = &api_tx.data_decoded {
+            if dangerous_methods.iter().any(|m| *m == decoded.method) {
+                warnings.dangerous_methods = true;
+            }
+        }
+
+        if let Err(e) = api::validate_safe_tx_hash(api_tx, &hashes.safe_tx_hash) {
+            warnings.argument_mismatches.push(e);
+        }
+
+        let confirmations = api_tx
+            .confirmations
+            .iter()
+            .filter_map(|confirmation| hex::decode(&confirmation.signature).ok())
+            .filter_map(|packed| unpack_signatures(&packed, hashes.safe_tx_hash).ok())
+            .flatten()
+            .collect::<Vec<_>>();
+        warnings.union(verify_contract_signatures(
+            &confirmations,
+            &CheckedHash::transaction(tx_args.safe_version.clone(), hashes.safe_tx_hash),
+            chain_id,
+            &address_book,
+        ));
+    }
+
+    let verifier = (!tx_args.offline).then(|| {
+        let kinds = if tx_args.verifier.is_empty() {
+            default_verifiers(chain_id)
+        } else {
+            tx_args.verifier.clone()
+        };
+        let cache = if tx_args.no_verification_cache {
+            None
+        } else {
+            VerificationCache::default_path().map(VerificationCache::load)
+        };
+        ContractVerifier::new(&kinds, cache)
+    });
+    let mut known_addresses = Vec::new();
+    if let Some(safe_info) = &safe_info {
+        known_addresses
+            .extend(safe_info.owners.iter().map(|owner| (*owner, "Safe owner".to_string())));
+    }
+    if let Ok(Some(api_tx)) = &api_tx {
+        known_addresses.extend(
+            api_tx
+                .confirmations
+                .iter()
+                .map(|confirmation| (confirmation.owner, "confirmed this transaction".to_string())),
+        );
+    }
+    let context = TransactionContext {
+        safe_address: tx_args.safe_address,
+        chain_id,
+        tokens: &tokens,
+        address_book: &address_book,
+    };
+    warnings.union(analyze_transaction(
+        &tx_data,
+        &context,
+        verifier,
+        tx_args.approval_threshold.as_deref(),
+        known_addresses,
+    ));
+    let onchain = tx_args
+        .rpc_url
+        .as_deref()
+        .and_then(|rpc_url| read_onchain(rpc_url, tx_args.safe_address, chain_id, &address_book));
+    if let Some(onchain) = &onchain {
+        warnings.union(check_onchain_safe(
+            onchain,
+            safe_info.as_ref(),
+            tx_args.safe_address,
+            chain_id,
+            &tx_args.safe_version,
+        ));
+    }
+    // The chain is the source of truth for the nonce, the API can lag behind
+    let current_nonce = match &onchain {
+        Some(onchain) => Some(onchain.nonce.saturating_to()),
+        None => safe_info.as_ref().map(|safe_info| safe_info.nonce),
+    };
+    let fetched_tx = api_tx.as_ref().ok().and_then(Option::as_ref);
+    warnings.union(check_nonce(tx_args.nonce, current_nonce, fetched_tx));
+    if let (true, Some(rpc_url)) = (tx_args.simulate, &tx_args.rpc_url) {
+        match simulate_on_rpc(rpc_url, chain_id, &tx_data, tx_args.safe_address, tx_args.nonce) {
+            Ok(simulation) => {
+                display_simulation(&simulation, &tokens, &address_book, chain_id);
+                warnings.union(check_simulation(&simulation));
+                warnings.union(check_safe_configuration(
+                    &simulation.safe_before,
+                    &simulation.safe_after,
+                    simulation.expected_nonce(),
+                ));
+            }
+            Err(e) => eprintln!("Warning: Could not simulate the transaction: {}", e),
+        }
+    }
+
+    // Display hashes
+    println!("\nMain transaction");
+    display_hashes(&hashes, tx_args.device);
+    if tx_args.explain {
+        match explain_safe_tx(
+            &tx_data,
+            tx_args.safe_address,
+            tx_args.nonce,
+            chain_id,
+            &tx_args.safe_version,
+        ) {
+            Ok(explanation) => display_eip712_explanation(&explanation),
+            Err(e) => eprintln!("Warning: Could not explain the encoding: {}", e),
+        }
+    }
+
+    // Calculate nested hashes
+    let levels = nested_levels(tx_args).expect("validated nested Safes");
+    let nested = nested_signing_hashes(
+        hashes.safe_tx_hash,
+        tx_args.safe_address,
+        &tx_args.safe_version,
+        &levels,
+        chain_id,
+    );
+    if !nested.is_empty() {
+        println!("\nNested Safes");
+        display_nested_hashes(tx_args.safe_address, &nested, tx_args.device);
+    }
+
+    let target = match nested.into_iter().last() {
+        Some(deepest) => SigningTarget {
+            safe_address: deepest.level.safe_address,
+            nonce: deepest.level.nonce.unwrap_or_default(),
+            tx_data: deepest.tx_data,
+            hashes: deepest.hashes,
+            confirmations: Vec::new(),
+            threshold: None,
+            blocking_warnings: warnings.blocks_signing(),
+        },
+        None => SigningTarget {
+            safe_address: tx_args.safe_address,
+            nonce: tx_args.nonce,
+            tx_data: Some(tx_data),
+            hashes,
+            confirmations: match &api_tx {
+                Ok(Some(api_tx)) => api_tx
+                    .confirmations
+                    .iter()
+                    .map(|confirmation| confirmation.signature.clone())
+                    .collect(),
+                _ => Vec::new(),
+            },
+            threshold: safe_info.map(|safe_info| safe_info.threshold),
+            blocking_warnings: warnings.blocks_signing(),
+        },
+    };
+
+    // Display warnings after the hashes
+    display_warnings(&warnings);
+
+    Some(target)
+}
+
+/// Reads the Safe through a JSON-RPC node and prints it, `None` with a warning when it cannot be
+/// read.
+fn read_onchain(
+    rpc_url: &str,
+    safe_address: Address,
+    chain_id: ChainId,
+    address_book: &AddressBook,
+) -> Option<OnchainSafe> {
+    let onchain = read_onchain_safe(&RpcClient::new(rpc_url), safe_address, chain_id)
+        .map_err(|e| eprintln!("Warning: Could not read the Safe from {}: {}", rpc_url, e))
+        .ok()?;
+    display_onchain_safe(&onchain, address_book, chain_id);
+    Some(onchain)
+}
+
+/// The Safe a transaction is analyzed for, and what its addresses and tokens are labeled with.
+struct TransactionContext<'a> {
+    safe_address: Address,
+    chain_id: ChainId,
+    tokens: &'a TokenRegistry,
+    address_book: &'a AddressBook,
+}
+
+/// Decodes the calls of a transaction, prints them and checks them and the transaction for
+/// suspicious content. `known_addresses` are trusted on top of the address book and the Safe.
+fn analyze_transaction(
+    tx_data: &TxInput,
+    context: &TransactionContext,
+    mut verifier: Option<ContractVerifier>,
+    approval_threshold: Option<&str>,
+    known_addresses: Vec<(Address, String)>,
+) -> SafeWarnings {
+    let TransactionContext { safe_address, chain_id, tokens, address_book } = *context;
+    let mut warnings = check_suspicious_content(tx_data, Some(chain_id), verifier.as_mut());
+
+    let calls = flatten_calls(Call {
+        operation: tx_data.operation,
+        to: tx_data.to,
+        value: tx_data.value,
+        data: tx_data.data.clone(),
+    });
+    display_multisend_calls(&calls, address_book, chain_id);
+    for call in &calls {
+        if let Some(token_call) = TokenCall::decode(&call.data) {
+            display_token_call(&token_call, tokens.get(call.to), address_book, chain_id);
+        }
+    }
+    warnings.union(check_token_call(&calls, tokens));
+    warnings.union(check_counterparties(&calls, chain_id, safe_address, address_book));
+    warnings.union(check_approvals(&calls, chain_id, tokens, address_book, approval_threshold));
+
+    let mut known: Vec<(Address, String)> = address_book
+        .entries()
+        .filter(|(entry_chain_id, _, _)| *entry_chain_id == chain_id)
+        .map(|(_, address, label)| (address, format!("address book: {}", label)))
+        .collect();
+    known.extend(known_addresses);
+    known.push((safe_address, "this Safe".to_string()));
+    warnings.union(check_address_poisoning(&transaction_addresses(tx_data, &calls), &known));
+
+    warnings
+}
+
+fn run_message(msg_args: &MessageArgs) -> (SafeHashes, SafeWarnings) {
+    let message = fs::read_to_string(&msg_args.input_file)
+        .unwrap_or_else(|_| panic!("Failed to read message file: {}", msg_args.input_file));
+    let msg_data =
+        MsgInput::decode(&message, msg_args.encoding, !msg_args.no_trim).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    verify_message(&msg_data, msg_args)
+}
+
+/// Prints the bytes of a message, its Sign-In with Ethereum fields and its Safe message hashes,
+/// returning the hashes and the warnings.
+fn verify_message(msg_data: &MsgInput, msg_args: &MessageArgs) -> (SafeHashes, SafeWarnings) {
+    let chain_id = ChainId::of(&msg_args.chain)
+        .unwrap_or_else(|_| panic!("chain {:?} is supported but id is not found", msg_args.chain));
+    display_message_bytes(&msg_data.message);
+
+    let mut warnings = SafeWarnings::new();
+    let text = std::str::from_utf8(&msg_data.message).unwrap_or_default();
+    match SiweMessage::parse(text) {
+        Ok(Some(siwe)) => {
+            display_siwe_message(&siwe);
+            warnings.union(check_siwe_message(
+                &siwe,
+                chain_id,
+                msg_args.safe_address,
+                chrono::Utc::now(),
+            ));
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: Malformed Sign-In with Ethereum message: {}", e),
+    }
+
+    let address_book = load_address_book(&msg_args.address_book);
+    let onchain = msg_args
+        .rpc_url
+        .as_deref()
+        .and_then(|rpc_url| read_onchain(rpc_url, msg_args.safe_address, chain_id, &address_book));
+    if let Some(onchain) = &onchain {
+        warnings.union(check_onchain_safe(
+            onchain,
+            None,
+            msg_args.safe_address,
+            chain_id,
+            &msg_args.safe_version,
+        ));
+    }
+
+    let hashes = msg_signing_hashes(msg_data, msg_args, chain_id);
+    display_message_hashes(&hashes, msg_args.device);
+    display_warnings(&warnings);
+
+    (hashes, warnings)
+}
+
+/// Decodes a call to a Safe module entry point, printing the hashes signers of module-gated flows
+/// verify and the warnings for the call the Safe executes.
+fn run_module(module_args: &ModuleArgs) {
+    let chain_id = ChainId::of(&module_args.chain).unwrap_or_else(|_| {
+        panic!("chain {:?} is supported but id is not found", module_args.chain)
+    });
+    let address_book = load_address_book(&module_args.address_book);
+
+    let Some(module_call) = decode_module_call(&module_args.data) else {
+        eprintln!("Calldata is not a call to a supported Safe module entry point");
+        std::process::exit(1);
+    };
+
+    let mut warnings = SafeWarnings::new();
+    match module_call {
+        ModuleCall::Transaction { entry_point, call } => {
+            let delay_hash = matches!(
+                entry_point,
+                ModuleEntryPoint::FromModule { .. } | ModuleEntryPoint::DelayExecuteNext
+            )
+            .then(|| delay_transaction_hash(&call));
+            display_module_transaction(&entry_point, &call, delay_hash, &address_book, chain_id);
+
+            let tx_data = TxInput::new(
+                call.to,
+                call.value,
+                call.data.clone(),
+                call.operation,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                Address::ZERO,
+                Address::ZERO,
+                String::new(),
+            );
+            warnings.union(check_suspicious_content(&tx_data, Some(chain_id), None));
+            let calls = flatten_calls(call);
+            display_multisend_calls(&calls, &address_book, chain_id);
+            warnings.delegatecall |= calls.iter().any(|call| call.operation == 1);
+        }
+        ModuleCall::AllowanceTransfer(transfer) => {
+            let transfer_hash = module_args
+                .module_address
+                .zip(module_args.allowance_nonce)
+                .map(|(module, nonce)| allowance_transfer_hash(&transfer, nonce, module, chain_id));
+            // An empty signature means the delegate sends the transfer itself
+            let signer = transfer_hash
+                .filter(|_| !transfer.signature.is_empty())
+                .map(|hash| OwnerSignature::ecdsa(&transfer.signature, hash).map(|s| s.owner));
+            let signer = match signer {
+                Some(Ok(signer)) => Some(signer),
+                Some(Err(e)) => {
+                    eprintln!("Warning: Could not recover the transfer signer: {}", e);
+                    None
+                }
+                None => None,
+            };
+            display_allowance_transfer(&transfer, transfer_hash, signer, &address_book, chain_id);
+            warnings.invalid_delegate_signature =
+                signer.is_some_and(|signer| signer != transfer.delegate);
+        }
+    }
+
+    display_warnings(&warnings);
+}
+
+/// Builds or decodes the deployment of a Safe and predicts the address of its proxy.
+fn run_predict(predict_args: &PredictArgs) {
+    let chain_id = ChainId::of(&predict_args.chain).unwrap_or_else(|_| {
+        panic!("chain {:?} is supported but id is not found", predict_args.chain)
+    });
+    let decode_hex = |name: &str, data: &str| {
+        hex::decode(data).unwrap_or_else(|e| {
+            eprintln!("Invalid {}: {}", name, e);
+            std::process::exit(1);
+        })
+    };
+
+    let creation = match &predict_args.calldata {
+        Some(calldata) => ProxyCreation::decode(&decode_hex("calldata", calldata))
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "Calldata is not a createProxyWithNonce or createChainSpecificProxyWithNonce call"
                 );
+                std::process::exit(1);
+            }),
+        None => {
+            let setup = SafeSetup {
+                owners: predict_args.owners.clone(),
+                threshold: U256::from(predict_args.threshold.expect("--threshold not provided")),
+                to: predict_args.setup_to,
+                data: decode_hex("setup data", &predict_args.setup_data).into(),
+                fallback_handler: predict_args.fallback_handler,
+                payment_token: Address::ZERO,
+                payment: U256::ZERO,
+                payment_receiver: Address::ZERO,
+            };
+            if let Err(e) = setup.validate() {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            ProxyCreation {
+                singleton: predict_args.singleton.expect("--singleton not provided"),
+                initializer: setup.encode(),
+                salt_nonce: predict_args.salt_nonce,
+                chain_specific: false,
+            }
+        }
+    };
+    let setup = SafeSetup::decode(&creation.initializer);
+    display_proxy_creation(&creation, setup.as_ref());
 
-                println!("\nNested transaction");
-                display_hashes(&nhashes);
+    let salt = creation.salt(chain_id);
+    let zksync = chain_id == ZKSYNC_CHAIN_ID;
+    let address = predict_args.factory.and_then(|factory| {
+        if zksync {
+            predict_args.proxy_bytecode_hash.map(|bytecode_hash| {
+                zksync_proxy_address(factory, salt, bytecode_hash, creation.singleton)
+            })
+        } else {
+            predict_args.proxy_creation_code.as_ref().map(|code| {
+                proxy_address(
+                    factory,
+                    salt,
+                    &decode_hex("proxy creation code", code),
+                    creation.singleton,
+                )
+            })
+        }
+    });
+    display_predicted_address(salt, address, zksync);
+
+    let mut warnings = SafeWarnings::new();
+    if let Some(setup) = &setup {
+        warnings.delegatecall = setup.to != Address::ZERO;
+        warnings.non_zero_gas_token = setup.payment_token != Address::ZERO;
+        warnings.non_zero_refund_receiver = setup.payment_receiver != Address::ZERO;
+    }
+    display_warnings(&warnings);
+}
+
+/// Verifies a `SafeTx` or `SafeMessage` payload the way `tx` and `msg` mode verify what they hash,
+/// and checks that it hashes the way the Safe does.
+fn analyze_safe_typed_data(
+    safe_typed_data: &SafeTypedData,
+    safe_version: SafeWalletVersion,
+    chain_id: ChainId,
+    eip712_hash: B256,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    let (primary_type, hashes) = match safe_typed_data {
+        SafeTypedData::Transaction { safe_address, nonce, tx } => {
+            let tokens = TokenRegistry::load(chain_id, &[] as &[PathBuf]).unwrap_or_default();
+            let address_book = load_address_book(&None);
+            let context = TransactionContext {
+                safe_address: *safe_address,
+                chain_id,
+                tokens: &tokens,
+                address_book: &address_book,
+            };
+            warnings.union(analyze_transaction(tx, &context, None, None, Vec::new()));
+            let hashes = tx_signing_hashes(tx, *safe_address, *nonce, chain_id, safe_version);
+            println!("\nSafe transaction");
+            display_hashes(&hashes, None);
+            ("SafeTx", hashes)
+        }
+        SafeTypedData::Message { safe_address, message } => {
+            println!();
+            display_message_bytes(message);
+            if let Ok(Some(siwe)) =
+                SiweMessage::parse(std::str::from_utf8(message).unwrap_or_default())
+            {
+                display_siwe_message(&siwe);
+                warnings.union(check_siwe_message(
+                    &siwe,
+                    chain_id,
+                    *safe_address,
+                    chrono::Utc::now(),
+                ));
             }
+            let hashes = safe_message_hashes(message, *safe_address, chain_id, safe_version);
+            display_message_hashes(&hashes, None);
+            ("SafeMessage", hashes)
+        }
+    };
 
-            // Display warnings after the hashes
-            display_warnings(&warnings);
+    if hashes.safe_tx_hash != eip712_hash {
+        warnings.typed_data_issues.push(TypedDataIssue::NotSafeHash {
+            primary_type: primary_type.to_string(),
+            eip712_hash,
+            safe_hash: hashes.safe_tx_hash,
+        });
+    }
+    warnings
+}
+
+/// Routes a dapp's signing request to the mode that hashes it the way the Safe wallet does.
+fn run_rpc_request(rpc_args: &RpcRequestArgs) {
+    let SigningRequest { chain_id, request } = read_input(Some(&rpc_args.input))
+        .and_then(|input| parse_rpc_request(&input))
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    let chain = match (&rpc_args.chain, chain_id) {
+        (Some(chain), Some(chain_id)) if ChainId::of(chain).ok() != Some(chain_id) => {
+            eprintln!("The request is for chain {}, not {}", chain_id, chain);
+            std::process::exit(1);
         }
-        Mode::Message(msg_args) => {
-            let chain_id = ChainId::of(&msg_args.chain).unwrap_or_else(|_| {
-                panic!("chain {:?} is supported but id is not found", msg_args.chain)
-            });
+        (Some(chain), _) => chain.clone(),
+        (None, Some(chain_id)) => get_chain_name(chain_id).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        (None, None) => {
+            eprintln!("The request has no chain, pass --chain");
+            std::process::exit(1);
+        }
+    };
+    println!("{:<24} {} on {}", "Request:", request.method(), chain);
 
-            let message = fs::read_to_string(&msg_args.input_file)
-                .unwrap_or_else(|_| panic!("Failed to read message file: {}", msg_args.input_file));
-            let msg_data = MsgInput::new(message.trim().to_string());
-            let hashes = msg_signing_hashes(&msg_data, &msg_args, chain_id);
-            display_message_hashes(&hashes);
+    match request {
+        RpcRequest::SendTransaction { from, to, value, data } => {
+            let Some(nonce) = rpc_args.nonce else {
+                eprintln!("eth_sendTransaction needs --nonce, the nonce the Safe proposes it with");
+                std::process::exit(1);
+            };
+            // The proposal is not in the transaction service yet
+            let tx_args = TransactionArgs {
+                chain,
+                nonce,
+                safe_address: from,
+                safe_version: rpc_args.safe_version.clone(),
+                to: Some(to),
+                value,
+                data,
+                offline: true,
+                address_book: rpc_args.address_book.clone(),
+                device: rpc_args.device,
+                ..Default::default()
+            };
+            run_transaction(&tx_args);
         }
-        Mode::Eip712(eip712_args) => {
-            let message = fs::read_to_string(&eip712_args.file).unwrap_or_else(|_| {
-                panic!("Failed to read file: {}", eip712_args.file.as_os_str().to_string_lossy())
-            });
-            let msg_data = Eip712Hasher::new(message);
-            let message = msg_data.hash().expect("Failed to EIP 712 hash");
-            display_eip712_hash(&message);
-
-            let Eip712Args { safe_version, chain, safe_address, standalone, .. } = eip712_args;
-
-            if !standalone {
-                let msg_hash = {
-                    let msg_hasher = MessageHasher::new_from_bytes(B256::from_slice(
-                        &hex::decode(message.eip_712_hash.clone()).unwrap(),
-                    ));
-
-                    msg_hasher.hash()
-                };
-
-                let domain_hash = {
-                    let domain_hasher = DomainHasher::new(
-                        safe_version.unwrap(),
-                        ChainId::of(&chain.unwrap()).unwrap(),
-                        safe_address.unwrap(),
-                    );
-
-                    domain_hasher.hash()
-                };
-
-                let mut buf = [0u8; 66];
-                buf[0] = 0x19;
-                buf[1] = 0x01;
-                buf[2..34].copy_from_slice(domain_hash.as_slice());
-                buf[34..].copy_from_slice(msg_hash.as_slice());
-                let safe_hash = keccak256(buf);
-
-                display_safe_ui_values_for_eip712(domain_hash, msg_hash, safe_hash);
+        RpcRequest::PersonalSign { address, message } => {
+            let msg_args = MessageArgs {
+                chain,
+                input_file: rpc_args.input.display().to_string(),
+                safe_address: address,
+                safe_version: rpc_args.safe_version.clone(),
+                encoding: MessageEncoding::Hex,
+                no_trim: true,
+                device: rpc_args.device,
+                rpc_url: None,
+                address_book: rpc_args.address_book.clone(),
+            };
+            verify_message(&MsgInput { message }, &msg_args);
+        }
+        RpcRequest::SignTypedData(input) => {
+            let eip712_args = Eip712Args {
+                chain: Some(chain),
+                safe_address: input.signer,
+                safe_version: Some(rpc_args.safe_version.clone()),
+                standalone: false,
+                expect_contract: None,
+                explain: false,
+                file: Some(rpc_args.input.clone()),
+            };
+            verify_typed_data(input, eip712_args);
+        }
+    }
+}
+
+/// Reads a file, or stdin when there is none or it is `-`.
+fn read_input(file: Option<&Path>) -> Result<String, String> {
+    match file.filter(|file| *file != Path::new("-")) {
+        Some(file) => fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read file {}: {}", file.display(), e)),
+        None => io::read_to_string(io::stdin()).map_err(|e| format!("Failed to read stdin: {}", e)),
+    }
+}
+
+/// Reads the typed data of `typed` mode and verifies it.
+fn run_eip712(eip712_args: Eip712Args) -> (B256, SafeWarnings) {
+    let input = read_input(eip712_args.file.as_deref())
+        .and_then(|input| parse_typed_data_input(&input))
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    verify_typed_data(input, eip712_args)
+}
+
+/// Prints the EIP-712 hashes of typed data, what it is recognized as and its warnings, returning
+/// the warnings with the Safe message hash, or the EIP-712 hash itself when standalone.
+fn verify_typed_data(input: TypedDataInput, eip712_args: Eip712Args) -> (B256, SafeWarnings) {
+    if let Some(signer) = input.signer {
+        println!("{:<24} {}", "Requested Signer:", signer);
+    }
+    let typed_data = serde_json::from_str::<alloy::dyn_abi::TypedData>(&input.json).ok();
+    let msg_data = Eip712Hasher::new(input.json);
+    let message = msg_data.hash().unwrap_or_else(|e| {
+        eprintln!("Invalid EIP-712 typed data: {}", e);
+        std::process::exit(1);
+    });
+    display_eip712_hash(&message);
+    if eip712_args.explain {
+        match typed_data
+            .as_ref()
+            .ok_or("invalid typed data".to_string())
+            .and_then(explain_typed_data)
+        {
+            Ok(explanation) => display_eip712_explanation(&explanation),
+            Err(e) => eprintln!("Warning: Could not explain the encoding: {}", e),
+        }
+    }
+    let eip712_hash = B256::from_slice(&hex::decode(&message.eip_712_hash).unwrap());
+
+    let mut warnings = SafeWarnings::new();
+    if let Some(typed_data) = &typed_data {
+        let signing_chain_id =
+            eip712_args.chain.as_deref().and_then(|chain| ChainId::of(chain).ok());
+        if let Some(safe_typed_data) = decode_safe_typed_data(typed_data) {
+            let chain_id = typed_data
+                .domain
+                .chain_id
+                .and_then(|chain_id| u64::try_from(chain_id).ok())
+                .or(signing_chain_id);
+            match chain_id {
+                Some(chain_id) => warnings.union(analyze_safe_typed_data(
+                    &safe_typed_data,
+                    safe_typed_data_version(typed_data),
+                    chain_id,
+                    eip712_hash,
+                )),
+                None => eprintln!(
+                    "Warning: The {} domain has no chainId, pass --chain to decode it",
+                    typed_data.primary_type
+                ),
             }
         }
+        warnings.union(check_domain(
+            typed_data,
+            signing_chain_id,
+            eip712_args.expect_contract,
+            eip712_args.safe_address,
+        ));
+        if let Some(summary) = summarize_typed_data(typed_data) {
+            let chain_id = typed_data
+                .domain
+                .chain_id
+                .and_then(|chain_id| u64::try_from(chain_id).ok())
+                .or(signing_chain_id)
+                .unwrap_or(1);
+            let tokens = TokenRegistry::load(chain_id, &[] as &[PathBuf]).unwrap_or_default();
+            display_typed_data_summary(&summary, &tokens);
+            let now = u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+            warnings.union(check_typed_data(&summary, typed_data, &tokens, now));
+        }
+    }
+    display_warnings(&warnings);
+
+    let Eip712Args { safe_version, chain, safe_address, standalone, .. } = eip712_args;
+
+    if standalone {
+        return (eip712_hash, warnings);
     }
+
+    let msg_hash = MessageHasher::new_from_bytes(eip712_hash).hash();
+
+    let chain = chain.expect("--chain is required unless standalone");
+    let chain_id = ChainId::of(&chain)
+        .unwrap_or_else(|_| panic!("chain {:?} is supported but id is not found", chain));
+    let domain_hash = {
+        let domain_hasher = DomainHasher::new(
+            safe_version.expect("--safe-version is required unless standalone"),
+            chain_id,
+            safe_address.expect("--safe-address is required unless standalone"),
+        );
+
+        domain_hasher.hash()
+    };
+
+    let mut buf = [0u8; 66];
+    buf[0] = 0x19;
+    buf[1] = 0x01;
+    buf[2..34].copy_from_slice(domain_hash.as_slice());
+    buf[34..].copy_from_slice(msg_hash.as_slice());
+    let safe_hash = keccak256(buf);
+
+    display_safe_ui_values_for_eip712(domain_hash, msg_hash, safe_hash);
+    (safe_hash, warnings)
 }
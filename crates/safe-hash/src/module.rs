@@ -0,0 +1,336 @@
+use crate::multisend::Call;
+use alloy::{
+    hex,
+    primitives::{Address, B256, Bytes, ChainId, U256, aliases::U96, keccak256},
+    sol,
+    sol_types::{Eip712Domain, SolCall, SolStruct},
+};
+
+sol! {
+    function execTransactionFromModule(address to, uint256 value, bytes data, uint8 operation);
+    function execTransactionFromModuleReturnData(
+        address to,
+        uint256 value,
+        bytes data,
+        uint8 operation
+    );
+    function executeNextTx(address to, uint256 value, bytes data, uint8 operation);
+    function execTransactionWithRole(
+        address to,
+        uint256 value,
+        bytes data,
+        uint8 operation,
+        bytes32 roleKey,
+        bool shouldRevert
+    );
+    function execTransactionWithRoleReturnData(
+        address to,
+        uint256 value,
+        bytes data,
+        uint8 operation,
+        bytes32 roleKey,
+        bool shouldRevert
+    );
+    function executeAllowanceTransfer(
+        address safe,
+        address token,
+        address to,
+        uint96 amount,
+        address paymentToken,
+        uint96 payment,
+        address delegate,
+        bytes signature
+    );
+
+    /// The struct the AllowanceModule's `generateTransferHash` hashes.
+    struct AllowanceTransfer {
+        address safe;
+        address token;
+        address to;
+        uint96 amount;
+        address paymentToken;
+        uint96 payment;
+        uint16 nonce;
+    }
+}
+
+/// The module entry point a call goes through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleEntryPoint {
+    /// `execTransactionFromModule(ReturnData)`, sent by a module to the Safe, or by an
+    /// authorized module to a Delay module to queue the call
+    FromModule { return_data: bool },
+    /// Delay `executeNextTx`, executing the oldest queued call once its cooldown has passed
+    DelayExecuteNext,
+    /// Zodiac Roles v2 `execTransactionWithRole(ReturnData)`
+    WithRole { role_key: B256, should_revert: bool, return_data: bool },
+}
+
+impl ModuleEntryPoint {
+    pub fn method(&self) -> &'static str {
+        match self {
+            ModuleEntryPoint::FromModule { return_data: false } => "execTransactionFromModule",
+            ModuleEntryPoint::FromModule { return_data: true } => {
+                "execTransactionFromModuleReturnData"
+            }
+            ModuleEntryPoint::DelayExecuteNext => "executeNextTx",
+            ModuleEntryPoint::WithRole { return_data: false, .. } => "execTransactionWithRole",
+            ModuleEntryPoint::WithRole { return_data: true, .. } => {
+                "execTransactionWithRoleReturnData"
+            }
+        }
+    }
+}
+
+/// An AllowanceModule transfer, paid out of a Safe's allowance for the delegate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowanceTransferCall {
+    pub safe: Address,
+    pub token: Address,
+    pub to: Address,
+    pub amount: U96,
+    pub payment_token: Address,
+    pub payment: U96,
+    pub delegate: Address,
+    pub signature: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleCall {
+    /// A call the Safe executes on behalf of a module
+    Transaction {
+        entry_point: ModuleEntryPoint,
+        call: Call,
+    },
+    AllowanceTransfer(AllowanceTransferCall),
+}
+
+/// Decodes calldata sent to a Safe module entry point, `None` for any other calldata.
+pub fn decode_module_call(data: &str) -> Option<ModuleCall> {
+    let bytes = hex::decode(data).ok()?;
+    let call = |to: Address, value: U256, data: &Bytes, operation: u8| Call {
+        operation,
+        to,
+        value,
+        data: hex::encode_prefixed(data),
+    };
+
+    let module_call = match bytes.get(..4)?.try_into().ok()? {
+        execTransactionFromModuleCall::SELECTOR => {
+            let c = execTransactionFromModuleCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::FromModule { return_data: false },
+                call: call(c.to, c.value, &c.data, c.operation),
+            }
+        }
+        execTransactionFromModuleReturnDataCall::SELECTOR => {
+            let c = execTransactionFromModuleReturnDataCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::FromModule { return_data: true },
+                call: call(c.to, c.value, &c.data, c.operation),
+            }
+        }
+        executeNextTxCall::SELECTOR => {
+            let c = executeNextTxCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::DelayExecuteNext,
+                call: call(c.to, c.value, &c.data, c.operation),
+            }
+        }
+        execTransactionWithRoleCall::SELECTOR => {
+            let c = execTransactionWithRoleCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::WithRole {
+                    role_key: c.roleKey,
+                    should_revert: c.shouldRevert,
+                    return_data: false,
+                },
+                call: call(c.to, c.value, &c.data, c.operation),
+            }
+        }
+        execTransactionWithRoleReturnDataCall::SELECTOR => {
+            let c = execTransactionWithRoleReturnDataCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::WithRole {
+                    role_key: c.roleKey,
+                    should_revert: c.shouldRevert,
+                    return_data: true,
+                },
+                call: call(c.to, c.value, &c.data, c.operation),
+            }
+        }
+        executeAllowanceTransferCall::SELECTOR => {
+            let c = executeAllowanceTransferCall::abi_decode(&bytes, true).ok()?;
+            ModuleCall::AllowanceTransfer(AllowanceTransferCall {
+                safe: c.safe,
+                token: c.token,
+                to: c.to,
+                amount: c.amount,
+                payment_token: c.paymentToken,
+                payment: c.payment,
+                delegate: c.delegate,
+                signature: c.signature,
+            })
+        }
+        _ => return None,
+    };
+
+    Some(module_call)
+}
+
+/// The hash a Delay module queues a call under, its `getTransactionHash`:
+/// `keccak256(abi.encodePacked(to, value, data, operation))`.
+pub fn delay_transaction_hash(call: &Call) -> B256 {
+    let mut packed = Vec::new();
+    packed.extend_from_slice(call.to.as_slice());
+    packed.extend_from_slice(&call.value.to_be_bytes::<32>());
+    packed.extend_from_slice(&hex::decode(&call.data).unwrap_or_default());
+    packed.push(call.operation);
+    keccak256(packed)
+}
+
+/// The hash the delegate signs for an AllowanceModule transfer, its `generateTransferHash`.
+/// `nonce` is the current nonce of the delegate's allowance for the token.
+pub fn allowance_transfer_hash(
+    transfer: &AllowanceTransferCall,
+    nonce: u16,
+    module_address: Address,
+    chain_id: ChainId,
+) -> B256 {
+    let domain = Eip712Domain {
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(module_address),
+        ..Default::default()
+    };
+    AllowanceTransfer {
+        safe: transfer.safe,
+        token: transfer.token,
+        to: transfer.to,
+        amount: transfer.amount,
+        paymentToken: transfer.payment_token,
+        payment: transfer.payment,
+        nonce,
+    }
+    .eip712_signing_hash(&domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{address, b256};
+
+    const SAFE: Address = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+    const TOKEN: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const MODULE: Address = address!("0xCFbFaC74C26F8647cBDb8c5caf80BB5b32E43134");
+
+    fn transfer() -> AllowanceTransferCall {
+        AllowanceTransferCall {
+            safe: SAFE,
+            token: TOKEN,
+            to: address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81"),
+            amount: U96::from(1_000_000),
+            payment_token: Address::ZERO,
+            payment: U96::ZERO,
+            delegate: address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1"),
+            signature: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn test_decode_module_call() {
+        let inner =
+            Call { operation: 0, to: TOKEN, value: U256::from(5), data: "0xa9059cbb".to_string() };
+        let data = hex::encode_prefixed(
+            execTransactionWithRoleCall {
+                to: inner.to,
+                value: inner.value,
+                data: Bytes::from_static(&[0xa9, 0x05, 0x9c, 0xbb]),
+                operation: 0,
+                roleKey: B256::repeat_byte(1),
+                shouldRevert: true,
+            }
+            .abi_encode(),
+        );
+
+        assert_eq!(
+            decode_module_call(&data),
+            Some(ModuleCall::Transaction {
+                entry_point: ModuleEntryPoint::WithRole {
+                    role_key: B256::repeat_byte(1),
+                    should_revert: true,
+                    return_data: false,
+                },
+                call: inner,
+            })
+        );
+
+        let data = hex::encode_prefixed(
+            executeAllowanceTransferCall {
+                safe: SAFE,
+                token: TOKEN,
+                to: transfer().to,
+                amount: transfer().amount,
+                paymentToken: Address::ZERO,
+                payment: U96::ZERO,
+                delegate: transfer().delegate,
+                signature: Bytes::new(),
+            }
+            .abi_encode(),
+        );
+        assert_eq!(decode_module_call(&data), Some(ModuleCall::AllowanceTransfer(transfer())));
+
+        assert_eq!(decode_module_call("0xa9059cbb"), None);
+    }
+
+    #[test]
+    fn test_delay_transaction_hash() {
+        let call =
+            Call { operation: 1, to: SAFE, value: U256::from(1), data: "0xabcd".to_string() };
+        let packed = hex::decode(concat!(
+            "1c694fc3006d81ff4a56f97e1b99529066a23725",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "abcd",
+            "01"
+        ))
+        .unwrap();
+        assert_eq!(delay_transaction_hash(&call), keccak256(packed));
+    }
+
+    #[test]
+    fn test_allowance_transfer_hash() {
+        let domain_separator = keccak256(
+            [
+                keccak256("EIP712Domain(uint256 chainId,address verifyingContract)").as_slice(),
+                &U256::from(1).to_be_bytes::<32>(),
+                MODULE.into_word().as_slice(),
+            ]
+            .concat(),
+        );
+        let typehash = keccak256(
+            "AllowanceTransfer(address safe,address token,address to,uint96 amount,address paymentToken,uint96 payment,uint16 nonce)",
+        );
+        assert_eq!(
+            typehash,
+            b256!("0x97c7ed08d51f4a077f71428543a8a2454799e5f6df78c03ef278be094511eda4")
+        );
+        let transfer = transfer();
+        let struct_hash = keccak256(
+            [
+                typehash.as_slice(),
+                SAFE.into_word().as_slice(),
+                TOKEN.into_word().as_slice(),
+                transfer.to.into_word().as_slice(),
+                &U256::from(1_000_000).to_be_bytes::<32>(),
+                B256::ZERO.as_slice(),
+                B256::ZERO.as_slice(),
+                &U256::from(3).to_be_bytes::<32>(),
+            ]
+            .concat(),
+        );
+        let expected = keccak256(
+            [&[0x19, 0x01], domain_separator.as_slice(), struct_hash.as_slice()].concat(),
+        );
+
+        assert_eq!(allowance_transfer_hash(&transfer, 3, MODULE, 1), expected);
+    }
+}
@@ -1,15 +1,49 @@
 use crate::{cli::MessageArgs, output::SafeHashes};
-use alloy::primitives::ChainId;
-use safe_utils::{DomainHasher, MessageHasher, SafeHasher};
+use alloy::{
+    hex,
+    primitives::{Address, B256, Bytes, ChainId, keccak256},
+    sol_types::SolValue,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::ValueEnum;
+use safe_utils::{DomainHasher, MessageHasher, SafeHasher, SafeWalletVersion};
+
+/// How the message file encodes the bytes that are signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageEncoding {
+    /// Text, signed as its UTF-8 bytes
+    Utf8,
+    /// Hex-encoded bytes, with or without `0x`, e.g. a 32-byte hash
+    Hex,
+    /// Base64-encoded bytes
+    Base64,
+}
 
 pub struct MsgInput {
-    pub message: String,
+    pub message: Bytes,
 }
 
 impl MsgInput {
     pub fn new(message: String) -> Self {
         let message_raw = message.replace("\r\n", "\n");
-        Self { message: message_raw }
+        Self { message: message_raw.into_bytes().into() }
+    }
+
+    /// Decodes the contents of a message file. Text is trimmed and its line endings normalized
+    /// unless `trim` is false, which keeps it byte for byte, while whitespace around hex and base64
+    /// payloads is never part of the message.
+    pub fn decode(input: &str, encoding: MessageEncoding, trim: bool) -> Result<Self, String> {
+        match encoding {
+            MessageEncoding::Utf8 if trim => Ok(Self::new(input.trim().to_string())),
+            MessageEncoding::Utf8 => Ok(Self { message: input.as_bytes().to_vec().into() }),
+            MessageEncoding::Hex => hex::decode(input.trim())
+                .map(|message| Self { message: message.into() })
+                .map_err(|e| format!("Invalid hex message: {}", e)),
+            MessageEncoding::Base64 => STANDARD
+                .decode(input.trim())
+                .map(|message| Self { message: message.into() })
+                .map_err(|e| format!("Invalid base64 message: {}", e)),
+        }
     }
 }
 
@@ -26,12 +60,12 @@ pub fn msg_signing_hashes(
     };
 
     let raw_message_hash = {
-        let message_hasher = MessageHasher::new(msg_data.message.clone());
+        let message_hasher = MessageHasher::new_from_raw_bytes(msg_data.message.clone());
         message_hasher.raw_hash()
     };
 
     let message_hash = {
-        let message_hasher = MessageHasher::new(msg_data.message.clone());
+        let message_hasher = MessageHasher::new_from_raw_bytes(msg_data.message.clone());
         message_hasher.hash()
     };
 
@@ -43,6 +77,27 @@ pub fn msg_signing_hashes(
     SafeHashes { domain_hash, message_hash, safe_tx_hash, raw_message_hash: Some(raw_message_hash) }
 }
 
+/// Hashes of a `SafeMessage` whose `message` bytes are given as is, as wallets receive it for
+/// `eth_signTypedData`. These bytes are usually the 32-byte hash of the original message.
+pub fn safe_message_hashes(
+    message: &[u8],
+    safe_address: Address,
+    chain_id: ChainId,
+    safe_version: SafeWalletVersion,
+) -> SafeHashes {
+    let domain_hash = DomainHasher::new(safe_version, chain_id, safe_address).hash();
+    let message_hash =
+        keccak256((keccak256("SafeMessage(bytes message)"), keccak256(message)).abi_encode());
+    let safe_tx_hash = SafeHasher::new(domain_hash, message_hash).hash();
+
+    SafeHashes {
+        domain_hash,
+        message_hash,
+        safe_tx_hash,
+        raw_message_hash: B256::try_from(message).ok(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,6 +115,11 @@ mod tests {
             input_file: "../../test/test_message.txt".to_string(),
             safe_address,
             safe_version: SafeWalletVersion::new(1, 3, 0),
+            encoding: MessageEncoding::Utf8,
+            no_trim: false,
+            device: None,
+            rpc_url: None,
+            address_book: None,
         };
 
         let message = fs::read_to_string(&args.input_file)
@@ -106,6 +166,11 @@ mod tests {
             input_file: "../../test/sign_in_message.txt".to_string(),
             safe_address,
             safe_version: SafeWalletVersion::new(1, 3, 0),
+            encoding: MessageEncoding::Utf8,
+            no_trim: false,
+            device: None,
+            rpc_url: None,
+            address_book: None,
         };
 
         // Read the Sign-In with Ethereum message
@@ -156,4 +221,76 @@ mod tests {
         assert_eq!(hashes.message_hash, expected_message, "Message hash mismatch");
         assert_eq!(hashes.safe_tx_hash, expected_safe, "Safe message hash mismatch");
     }
+
+    #[test]
+    fn test_decode_message() {
+        let decode =
+            |input, encoding, trim| MsgInput::decode(input, encoding, trim).unwrap().message;
+
+        assert_eq!(decode(" hi\r\n", MessageEncoding::Utf8, true), Bytes::from_static(b"hi"));
+        assert_eq!(decode(" hi\r\n", MessageEncoding::Utf8, false), Bytes::from_static(b" hi\r\n"));
+        assert_eq!(
+            decode("0xdead\n", MessageEncoding::Hex, false),
+            Bytes::from_static(&[0xde, 0xad])
+        );
+        assert_eq!(
+            decode("3q0=\n", MessageEncoding::Base64, true),
+            Bytes::from_static(&[0xde, 0xad])
+        );
+        assert!(MsgInput::decode("0xdeadbeef", MessageEncoding::Base64, true).is_err());
+        assert!(MsgInput::decode("hi", MessageEncoding::Hex, true).is_err());
+    }
+
+    #[test]
+    fn test_no_trim_keeps_crlf_line_endings() {
+        let message = "Sign in\r\nNonce: 1\r\n";
+        let exact = MsgInput::decode(message, MessageEncoding::Utf8, false).unwrap();
+        let normalized = MsgInput::decode(message, MessageEncoding::Utf8, true).unwrap();
+
+        assert_eq!(exact.message, Bytes::from_static(message.as_bytes()));
+        assert_eq!(normalized.message, Bytes::from_static(b"Sign in\nNonce: 1"));
+        assert_ne!(
+            MessageHasher::new_from_raw_bytes(exact.message).raw_hash(),
+            MessageHasher::new_from_raw_bytes(normalized.message).raw_hash()
+        );
+    }
+
+    #[test]
+    fn test_hex_message_hashes_the_bytes() {
+        let hash = "0xad06b099fca34e51e4886643d95d9a19ace2cd024065efb66662a876e8c40343";
+        let msg_data = MsgInput::decode(hash, MessageEncoding::Hex, true).unwrap();
+
+        assert_eq!(msg_data.message.len(), 32);
+        assert_eq!(
+            MessageHasher::new_from_raw_bytes(msg_data.message).raw_hash(),
+            alloy::primitives::eip191_hash_message(hex::decode(hash).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_safe_message_hashes() {
+        let safe_address = Address::from_str("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1").unwrap();
+        let args = MessageArgs {
+            chain: "sepolia".to_string(),
+            input_file: "../../test/test_message.txt".to_string(),
+            safe_address,
+            safe_version: SafeWalletVersion::new(1, 3, 0),
+            encoding: MessageEncoding::Utf8,
+            no_trim: false,
+            device: None,
+            rpc_url: None,
+            address_book: None,
+        };
+        let msg_data = MsgInput::new(fs::read_to_string(&args.input_file).unwrap());
+        let expected = msg_signing_hashes(&msg_data, &args, 11155111);
+
+        // The typed data carries the EIP-191 hash of the text as its `message` bytes
+        let raw_hash = expected.raw_message_hash.unwrap();
+        let hashes =
+            safe_message_hashes(raw_hash.as_slice(), safe_address, 11155111, args.safe_version);
+        assert_eq!(hashes.domain_hash, expected.domain_hash);
+        assert_eq!(hashes.message_hash, expected.message_hash);
+        assert_eq!(hashes.safe_tx_hash, expected.safe_tx_hash);
+        assert_eq!(hashes.raw_message_hash, Some(raw_hash));
+    }
 }
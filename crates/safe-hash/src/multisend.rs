@@ -0,0 +1,124 @@
+use alloy::{
+    hex,
+    primitives::{Address, U256},
+    sol,
+    sol_types::SolCall,
+};
+
+sol! {
+    function multiSend(bytes transactions);
+}
+
+/// A single call executed by the Safe, either the transaction itself or one entry of a MultiSend
+/// batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub operation: u8,
+    pub to: Address,
+    pub value: U256,
+    pub data: String,
+}
+
+/// Decodes `multiSend(bytes)` calldata into its packed sub-calls.
+///
+/// Each entry is packed as `operation (1) | to (20) | value (32) | data length (32) | data`.
+pub fn decode_multisend(data: &str) -> Option<Vec<Call>> {
+    let bytes = hex::decode(data).ok()?;
+    if bytes.get(..4)? != multiSendCall::SELECTOR {
+        return None;
+    }
+    let packed = multiSendCall::abi_decode(&bytes, true).ok()?.transactions;
+
+    let mut calls = Vec::new();
+    let mut rest = &packed[..];
+    while !rest.is_empty() {
+        if rest.len() < 85 {
+            return None;
+        }
+        let operation = rest[0];
+        let to = Address::from_slice(&rest[1..21]);
+        let value = U256::from_be_slice(&rest[21..53]);
+        let len: usize = U256::from_be_slice(&rest[53..85]).try_into().ok()?;
+        let end = 85usize.checked_add(len)?;
+        let data = rest.get(85..end)?;
+        calls.push(Call { operation, to, value, data: hex::encode_prefixed(data) });
+        rest = &rest[end..];
+    }
+
+    Some(calls)
+}
+
+/// Returns the call itself followed by every sub-call of (possibly nested) MultiSend batches.
+pub fn flatten_calls(call: Call) -> Vec<Call> {
+    let mut calls = Vec::new();
+    if let Some(sub_calls) = decode_multisend(&call.data) {
+        calls.push(call);
+        for sub_call in sub_calls {
+            calls.extend(flatten_calls(sub_call));
+        }
+    } else {
+        calls.push(call);
+    }
+    calls
+}
+
+/// Encodes `multiSend(bytes)` calldata for `calls`, the inverse of [`decode_multisend`].
+#[cfg(test)]
+pub fn encode_multisend(calls: &[Call]) -> String {
+    let mut packed = Vec::new();
+    for call in calls {
+        let data = hex::decode(&call.data).unwrap();
+        packed.push(call.operation);
+        packed.extend_from_slice(call.to.as_slice());
+        packed.extend_from_slice(&call.value.to_be_bytes::<32>());
+        packed.extend_from_slice(&U256::from(data.len()).to_be_bytes::<32>());
+        packed.extend_from_slice(&data);
+    }
+    hex::encode_prefixed(multiSendCall { transactions: packed.into() }.abi_encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn test_decode_multisend() {
+        let calls = vec![
+            Call {
+                operation: 0,
+                to: address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                value: U256::ZERO,
+                data: "0x095ea7b3".to_string(),
+            },
+            Call {
+                operation: 1,
+                to: address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC"),
+                value: U256::from(5),
+                data: "0x".to_string(),
+            },
+        ];
+
+        assert_eq!(decode_multisend(&encode_multisend(&calls)), Some(calls));
+        assert_eq!(decode_multisend("0xa9059cbb"), None);
+    }
+
+    #[test]
+    fn test_flatten_nested_multisend() {
+        let leaf = Call {
+            operation: 0,
+            to: address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            value: U256::ZERO,
+            data: "0x095ea7b3".to_string(),
+        };
+        let inner = Call {
+            operation: 1,
+            to: address!("0x40A2aCCbd92BCA938b02010E17A5b8929b49130D"),
+            value: U256::ZERO,
+            data: encode_multisend(&[leaf.clone()]),
+        };
+        let outer = Call { data: encode_multisend(&[inner.clone()]), ..inner.clone() };
+
+        assert_eq!(flatten_calls(outer.clone()), vec![outer, inner, leaf]);
+    }
+}
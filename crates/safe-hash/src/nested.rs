@@ -0,0 +1,295 @@
+use crate::{
+    cli::TransactionArgs,
+    output::SafeHashes,
+    tx_signing::{TxInput, tx_signing_hashes},
+};
+use alloy::{
+    hex,
+    primitives::{Address, B256, ChainId, U256},
+    sol,
+    sol_types::SolCall,
+};
+use clap::ValueEnum;
+use safe_utils::{CheckedHash, DomainHasher, SafeWalletVersion, safe_message_struct_hash};
+
+sol! {
+    function approveHash(bytes32 hashToApprove);
+}
+
+/// How a Safe owning another Safe approves the hash of the level above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NestedApproval {
+    /// A transaction calling `approveHash` on the owned Safe
+    ApproveHash,
+    /// An EIP-1271 signature: the owners sign the hash as a `SafeMessage` of the owner Safe
+    SafeMessage,
+}
+
+/// A Safe in the chain of owners, from the Safe closest to the transaction outwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedLevel {
+    pub safe_address: Address,
+    pub safe_version: SafeWalletVersion,
+    pub approval: NestedApproval,
+    /// Nonce of the `approveHash` transaction, `None` for `SafeMessage` approvals
+    pub nonce: Option<u64>,
+}
+
+pub struct NestedHashes {
+    pub level: NestedLevel,
+    /// The hash of the level above, which this level approves
+    pub approved_hash: B256,
+    /// The `approveHash` transaction, `None` for `SafeMessage` approvals
+    pub tx_data: Option<TxInput>,
+    pub hashes: SafeHashes,
+}
+
+/// Pairs the nested Safe arguments into levels. Versions default to `--safe-version` and
+/// approvals to `approveHash`; nonces are taken, in order, by the `approveHash` levels.
+pub fn nested_levels(tx_args: &TransactionArgs) -> Result<Vec<NestedLevel>, String> {
+    let depth = tx_args.nested_safe_address.len();
+    if tx_args.nested_safe_version.len() > depth || tx_args.nested_approval.len() > depth {
+        return Err(
+            "more --nested-safe-version or --nested-approval values than nested Safes".to_string()
+        );
+    }
+
+    let mut nonces = tx_args.nested_safe_nonce.iter();
+    let mut levels = Vec::with_capacity(depth);
+    for (i, safe_address) in tx_args.nested_safe_address.iter().enumerate() {
+        let approval =
+            tx_args.nested_approval.get(i).copied().unwrap_or(NestedApproval::ApproveHash);
+        let nonce = match approval {
+            NestedApproval::ApproveHash => Some(*nonces.next().ok_or_else(|| {
+                format!("--nested-safe-nonce is required for nested Safe {}", safe_address)
+            })?),
+            NestedApproval::SafeMessage => None,
+        };
+        levels.push(NestedLevel {
+            safe_address: *safe_address,
+            safe_version: tx_args
+                .nested_safe_version
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| tx_args.safe_version.clone()),
+            approval,
+            nonce,
+        });
+    }
+    if nonces.next().is_some() {
+        return Err("more --nested-safe-nonce values than approveHash levels".to_string());
+    }
+
+    Ok(levels)
+}
+
+/// Computes the hashes of every level, each approving the hash of the level before it, starting
+/// with `safe_tx_hash` of `safe_address` running `safe_version`.
+pub fn nested_signing_hashes(
+    safe_tx_hash: B256,
+    safe_address: Address,
+    safe_version: &SafeWalletVersion,
+    levels: &[NestedLevel],
+    chain_id: ChainId,
+) -> Vec<NestedHashes> {
+    let mut checked = CheckedHash::transaction(safe_version.clone(), safe_tx_hash);
+    let mut approved_safe = safe_address;
+    let mut nested = Vec::with_capacity(levels.len());
+
+    for level in levels {
+        let approved_hash = checked.hash;
+        let (tx_data, hashes) = match level.approval {
+            NestedApproval::ApproveHash => {
+                let data = approveHashCall { hashToApprove: approved_hash }.abi_encode();
+                let tx_data = TxInput::new(
+                    approved_safe,
+                    U256::ZERO,
+                    hex::encode_prefixed(data),
+                    0,
+                    U256::ZERO,
+                    U256::ZERO,
+                    U256::ZERO,
+                    Address::ZERO,
+                    Address::ZERO,
+                    String::new(),
+                );
+                let hashes = tx_signing_hashes(
+                    &tx_data,
+                    level.safe_address,
+                    level.nonce.expect("approveHash levels have a nonce"),
+                    chain_id,
+                    level.safe_version.clone(),
+                );
+                checked = CheckedHash::transaction(level.safe_version.clone(), hashes.safe_tx_hash);
+                (Some(tx_data), hashes)
+            }
+            NestedApproval::SafeMessage => {
+                // The message depends on how the approved Safe asks its contract owners
+                let message = checked.owner_message();
+                checked =
+                    checked.for_owner(level.safe_address, level.safe_version.clone(), chain_id);
+                let hashes = SafeHashes {
+                    raw_message_hash: Some(message),
+                    domain_hash: DomainHasher::new(
+                        level.safe_version.clone(),
+                        chain_id,
+                        level.safe_address,
+                    )
+                    .hash(),
+                    message_hash: safe_message_struct_hash(message),
+                    safe_tx_hash: checked.hash,
+                };
+                (None, hashes)
+            }
+        };
+
+        nested.push(NestedHashes { level: level.clone(), approved_hash, tx_data, hashes });
+        approved_safe = level.safe_address;
+    }
+
+    nested
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{address, b256, keccak256};
+
+    const SAFE_TX_HASH: B256 =
+        b256!("0xad06b099fca34e51e4886643d95d9a19ace2cd024065efb66662a876e8c40343");
+    const PARENT: Address = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+    const CHILD: Address = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+    const GRANDCHILD: Address = address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1");
+
+    fn tx_args(approvals: Vec<NestedApproval>, nonces: Vec<u64>) -> TransactionArgs {
+        TransactionArgs {
+            safe_version: SafeWalletVersion::new(1, 4, 1),
+            nested_safe_address: vec![CHILD, GRANDCHILD],
+            nested_safe_version: vec![SafeWalletVersion::new(1, 3, 0)],
+            nested_approval: approvals,
+            nested_safe_nonce: nonces,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_nested_levels() {
+        let levels = nested_levels(&tx_args(vec![NestedApproval::SafeMessage], vec![9])).unwrap();
+        assert_eq!(levels, vec![
+            NestedLevel {
+                safe_address: CHILD,
+                safe_version: SafeWalletVersion::new(1, 3, 0),
+                approval: NestedApproval::SafeMessage,
+                nonce: None,
+            },
+            NestedLevel {
+                safe_address: GRANDCHILD,
+                safe_version: SafeWalletVersion::new(1, 4, 1),
+                approval: NestedApproval::ApproveHash,
+                nonce: Some(9),
+            },
+        ]);
+
+        assert!(nested_levels(&tx_args(vec![], vec![1])).is_err());
+        assert!(nested_levels(&tx_args(vec![NestedApproval::SafeMessage], vec![1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_nested_signing_hashes_chain_levels() {
+        let levels = nested_levels(&tx_args(vec![], vec![1, 2])).unwrap();
+        let nested = nested_signing_hashes(
+            SAFE_TX_HASH,
+            PARENT,
+            &SafeWalletVersion::new(1, 4, 1),
+            &levels,
+            1,
+        );
+
+        assert_eq!(nested.len(), 2);
+        let first_tx = nested[0].tx_data.as_ref().unwrap();
+        assert_eq!(first_tx.to, PARENT);
+        assert_eq!(first_tx.data, format!("0xd4d9bdcd{}", hex::encode(SAFE_TX_HASH)));
+        assert_eq!(nested[1].approved_hash, nested[0].hashes.safe_tx_hash);
+        assert_eq!(nested[1].tx_data.as_ref().unwrap().to, CHILD);
+    }
+
+    /// `getMessageHashForSafe` of the `CompatibilityFallbackHandler` for a message whose keccak is
+    /// `message`.
+    fn message_hash_for_safe(safe: Address, version: SafeWalletVersion, message: B256) -> B256 {
+        let typehash = keccak256("SafeMessage(bytes message)");
+        let safe_message_hash = keccak256([typehash, message].concat());
+        let domain_separator = DomainHasher::new(version, 1, safe).hash();
+        keccak256(
+            [&[0x19, 0x01], domain_separator.as_slice(), safe_message_hash.as_slice()].concat(),
+        )
+    }
+
+    fn safe_message_level(safe_address: Address, minor: u64, patch: u64) -> NestedLevel {
+        NestedLevel {
+            safe_address,
+            safe_version: SafeWalletVersion::new(1, minor, patch),
+            approval: NestedApproval::SafeMessage,
+            nonce: None,
+        }
+    }
+
+    #[test]
+    fn test_safe_message_approval() {
+        // A 1.4.1 Safe asks its owners to validate the transaction data, whose keccak is the hash
+        let parent = SafeWalletVersion::new(1, 4, 1);
+        let nested = nested_signing_hashes(
+            SAFE_TX_HASH,
+            PARENT,
+            &parent,
+            &[safe_message_level(CHILD, 3, 0)],
+            1,
+        );
+
+        assert!(nested[0].tx_data.is_none());
+        assert_eq!(nested[0].approved_hash, SAFE_TX_HASH);
+        assert_eq!(nested[0].hashes.raw_message_hash, Some(SAFE_TX_HASH));
+        assert_eq!(
+            nested[0].hashes.safe_tx_hash,
+            message_hash_for_safe(CHILD, SafeWalletVersion::new(1, 3, 0), SAFE_TX_HASH)
+        );
+
+        // A 1.5.0 Safe passes the hash itself, which the handler encodes as bytes
+        let parent = SafeWalletVersion::new(1, 5, 0);
+        let nested = nested_signing_hashes(
+            SAFE_TX_HASH,
+            PARENT,
+            &parent,
+            &[safe_message_level(CHILD, 3, 0)],
+            1,
+        );
+        assert_eq!(
+            nested[0].hashes.safe_tx_hash,
+            message_hash_for_safe(CHILD, SafeWalletVersion::new(1, 3, 0), keccak256(SAFE_TX_HASH))
+        );
+    }
+
+    #[test]
+    fn test_nested_safe_message_approvals() {
+        let parent = SafeWalletVersion::new(1, 3, 0);
+
+        // A 1.3.0 owner Safe checks its owners with the data it was given
+        let levels = [safe_message_level(CHILD, 3, 0), safe_message_level(GRANDCHILD, 3, 0)];
+        let nested = nested_signing_hashes(SAFE_TX_HASH, PARENT, &parent, &levels, 1);
+        assert_eq!(nested[1].approved_hash, nested[0].hashes.safe_tx_hash);
+        assert_eq!(
+            nested[1].hashes.safe_tx_hash,
+            message_hash_for_safe(GRANDCHILD, SafeWalletVersion::new(1, 3, 0), SAFE_TX_HASH)
+        );
+
+        // A 1.4.1 owner Safe checks them with its SafeMessage preimage
+        let levels = [safe_message_level(CHILD, 4, 1), safe_message_level(GRANDCHILD, 3, 0)];
+        let nested = nested_signing_hashes(SAFE_TX_HASH, PARENT, &parent, &levels, 1);
+        let child_hash =
+            message_hash_for_safe(CHILD, SafeWalletVersion::new(1, 4, 1), SAFE_TX_HASH);
+        assert_eq!(nested[0].hashes.safe_tx_hash, child_hash);
+        assert_eq!(
+            nested[1].hashes.safe_tx_hash,
+            message_hash_for_safe(GRANDCHILD, SafeWalletVersion::new(1, 3, 0), child_hash)
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{
+    api::SafeInfo,
+    output::{OnchainMismatch, SafeWarnings},
+    predict::SENTINEL_OWNERS,
+    simulate::{SINGLETON_SLOT, guard_slot},
+    warn::format_addresses,
+};
+use alloy::{
+    primitives::{Address, B256, Bytes, ChainId, U64, U256},
+    sol,
+    sol_types::SolCall,
+};
+use safe_utils::{DomainHasher, SafeWalletVersion};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+sol! {
+    function nonce() returns (uint256);
+    function getThreshold() returns (uint256);
+    function getOwners() returns (address[]);
+    function VERSION() returns (string);
+    function getModulesPaginated(address start, uint256 pageSize)
+        returns (address[] array, address next);
+    function getModules() returns (address[]);
+    function domainSeparator() returns (bytes32);
+}
+
+/// Modules read in one `getModulesPaginated` call, more than any Safe is expected to enable.
+const MODULES_PAGE_SIZE: u64 = 100;
+
+/// A plain JSON-RPC endpoint, e.g. a local anvil node.
+pub struct RpcClient {
+    client: reqwest::blocking::Client,
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self { client: reqwest::blocking::Client::new(), url: url.to_string() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, String> {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|e| format!("{} failed: {}", method, e))?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error));
+        }
+        serde_json::from_value(response["result"].clone())
+            .map_err(|e| format!("{} returned an unexpected result: {}", method, e))
+    }
+
+    pub fn chain_id(&self) -> Result<ChainId, String> {
+        let chain_id: U64 = self.request("eth_chainId", json!([]))?;
+        Ok(chain_id.to())
+    }
+
+    fn call<C: SolCall>(&self, to: Address, call: C) -> Result<C::Return, String> {
+        let params = json!([{"to": to, "data": Bytes::from(call.abi_encode())}, "latest"]);
+        let output: Bytes = self.request("eth_call", params)?;
+        C::abi_decode_returns(&output, true)
+            .map_err(|e| format!("{} returned an unexpected result: {}", C::SIGNATURE, e))
+    }
+
+    fn storage(&self, address: Address, slot: U256) -> Result<U256, String> {
+        let value: B256 = self.request("eth_getStorageAt", json!([address, slot, "latest"]))?;
+        Ok(value.into())
+    }
+}
+
+/// What the Safe contract itself reports, at the latest block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnchainSafe {
+    pub version: String,
+    pub nonce: U256,
+    pub threshold: U256,
+    pub owners: Vec<Address>,
+    pub singleton: Address,
+    pub guard: Address,
+    pub modules: Vec<Address>,
+    pub domain_separator: B256,
+}
+
+/// Reads a Safe through its getters, and the singleton and guard from its storage.
+pub fn read_onchain_safe(
+    client: &RpcClient,
+    safe_address: Address,
+    chain_id: ChainId,
+) -> Result<OnchainSafe, String> {
+    let node_chain_id = client.chain_id()?;
+    if node_chain_id != chain_id {
+        return Err(format!(
+            "{} is a node of chain {}, not {}",
+            client.url(),
+            node_chain_id,
+            chain_id
+        ));
+    }
+    let code: Bytes = client.request("eth_getCode", json!([safe_address, "latest"]))?;
+    if code.is_empty() {
+        return Err(format!("{} has no code on chain {}", safe_address, chain_id));
+    }
+
+    // `getModulesPaginated` was added in 1.1.0, `getModules` removed in 1.3.0
+    let modules = client
+        .call(safe_address, getModulesPaginatedCall {
+            start: SENTINEL_OWNERS,
+            pageSize: U256::from(MODULES_PAGE_SIZE),
+        })
+        .map(|modules| modules.array)
+        .or_else(|_| client.call(safe_address, getModulesCall {}).map(|modules| modules._0))?;
+    Ok(OnchainSafe {
+        version: client.call(safe_address, VERSIONCall {})?._0,
+        nonce: client.call(safe_address, nonceCall {})?._0,
+        threshold: client.call(safe_address, getThresholdCall {})?._0,
+        owners: client.call(safe_address, getOwnersCall {})?._0,
+        singleton: Address::from_word(client.storage(safe_address, SINGLETON_SLOT)?.into()),
+        guard: Address::from_word(client.storage(safe_address, guard_slot())?.into()),
+        modules,
+        domain_separator: client.call(safe_address, domainSeparatorCall {})?._0,
+    })
+}
+
+/// Compares what the Safe contract reports with the API, the `--safe-version` argument and the
+/// domain hash computed from it.
+pub fn check_onchain_safe(
+    onchain: &OnchainSafe,
+    safe_info: Option<&SafeInfo>,
+    safe_address: Address,
+    chain_id: ChainId,
+    safe_version: &SafeWalletVersion,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    let mut compare =
+        |field: &'static str, source: &'static str, onchain: String, other: String| {
+            if onchain != other {
+                warnings.onchain_mismatches.push(OnchainMismatch {
+                    field: field.to_string(),
+                    onchain,
+                    source,
+                    expected: other,
+                });
+            }
+        };
+
+    compare("Version", "User Supplied", onchain.version.clone(), safe_version.to_string());
+    if let Some(safe_info) = safe_info {
+        // The API reports build metadata, e.g. `1.3.0+L2`
+        let api_version = safe_info.version.as_deref().map(|v| v.split('+').next().unwrap_or(v));
+        if let Some(api_version) = api_version {
+            compare("Version", "API Returned", onchain.version.clone(), api_version.to_string());
+        }
+        compare("Nonce", "API Returned", onchain.nonce.to_string(), safe_info.nonce.to_string());
+        compare(
+            "Threshold",
+            "API Returned",
+            onchain.threshold.to_string(),
+            safe_info.threshold.to_string(),
+        );
+        compare(
+            "Owners",
+            "API Returned",
+            format_addresses(&onchain.owners),
+            format_addresses(&safe_info.owners),
+        );
+        if let Some(master_copy) = safe_info.master_copy {
+            compare(
+                "Singleton",
+                "API Returned",
+                onchain.singleton.to_string(),
+                master_copy.to_string(),
+            );
+        }
+        compare(
+            "Guard",
+            "API Returned",
+            onchain.guard.to_string(),
+            safe_info.guard.unwrap_or_default().to_string(),
+        );
+        compare(
+            "Modules",
+            "API Returned",
+            format_addresses(&onchain.modules),
+            format_addresses(&safe_info.modules),
+        );
+    }
+
+    let domain_hash = DomainHasher::new(safe_version.clone(), chain_id, safe_address).hash();
+    // Point at the version the contract's domain separator is computed for
+    let field = match SafeWalletVersion::parse(&onchain.version) {
+        Ok(version)
+            if DomainHasher::new(version.clone(), chain_id, safe_address).hash()
+                == onchain.domain_separator
+                && version != *safe_version =>
+        {
+            "Domain Separator (matches the on-chain version)"
+        }
+        _ => "Domain Separator",
+    };
+    compare(field, "Computed", onchain.domain_separator.to_string(), domain_hash.to_string());
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    const SAFE: Address = address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1");
+    const OWNER: Address = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+    const SINGLETON: Address = address!("0x29fcB43b46531BcA003ddC8FCB67FFE91900C762");
+    const MODULE: Address = address!("0x1111111111111111111111111111111111111111");
+
+    /// The result of a JSON-RPC call on a node with a single owner Safe of `version`, or the error
+    /// of a reverting call.
+    fn onchain_result(method: &str, params: &Value, version: &str) -> Result<Value, Value> {
+        let safe_version = SafeWalletVersion::parse(version).unwrap();
+        let result = match method {
+            "eth_chainId" => json!("0xaa36a7"),
+            "eth_getCode" => json!("0x6080"),
+            "eth_getStorageAt" if params[1] == json!(SINGLETON_SLOT) => {
+                json!(SINGLETON.into_word())
+            }
+            "eth_getStorageAt" => json!(B256::ZERO),
+            "eth_call" => {
+                let data: Bytes = serde_json::from_value(params[0]["data"].clone()).unwrap();
+                let output = match <[u8; 4]>::try_from(&data[..4]).unwrap() {
+                    VERSIONCall::SELECTOR => {
+                        VERSIONCall::abi_encode_returns(&(version.to_string(),))
+                    }
+                    nonceCall::SELECTOR => nonceCall::abi_encode_returns(&(U256::from(9),)),
+                    getThresholdCall::SELECTOR => {
+                        getThresholdCall::abi_encode_returns(&(U256::from(1),))
+                    }
+                    getOwnersCall::SELECTOR => getOwnersCall::abi_encode_returns(&(vec![OWNER],)),
+                    getModulesPaginatedCall::SELECTOR
+                        if safe_version < SafeWalletVersion::new(1, 1, 0) =>
+                    {
+                        return Err(json!({"code": 3, "message": "execution reverted"}));
+                    }
+                    getModulesCall::SELECTOR => {
+                        getModulesCall::abi_encode_returns(&(vec![MODULE],))
+                    }
+                    getModulesPaginatedCall::SELECTOR => {
+                        getModulesPaginatedCall::abi_encode_returns(&(
+                            Vec::<Address>::new(),
+                            SENTINEL_OWNERS,
+                        ))
+                    }
+                    domainSeparatorCall::SELECTOR => {
+                        let hash = DomainHasher::new(safe_version, 11155111, SAFE).hash();
+                        domainSeparatorCall::abi_encode_returns(&(hash,))
+                    }
+                    selector => panic!("unexpected call {:?}", selector),
+                };
+                json!(Bytes::from(output))
+            }
+            method => panic!("unexpected method {}", method),
+        };
+        Ok(result)
+    }
+
+    /// Serves the JSON-RPC requests of a node with a single owner Safe of `version` on Sepolia.
+    fn fake_node(version: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let response = match onchain_result(
+                    request["method"].as_str().unwrap(),
+                    &request["params"],
+                    version,
+                ) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": 1, "result": result}),
+                    Err(error) => json!({"jsonrpc": "2.0", "id": 1, "error": error}),
+                }
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_read_onchain_safe() {
+        let client = RpcClient::new(&fake_node("1.4.1"));
+        let onchain = read_onchain_safe(&client, SAFE, 11155111).unwrap();
+        assert_eq!(onchain.version, "1.4.1");
+        assert_eq!(onchain.nonce, U256::from(9));
+        assert_eq!(onchain.owners, [OWNER]);
+        assert_eq!(onchain.singleton, SINGLETON);
+        assert_eq!(onchain.guard, Address::ZERO);
+        assert!(onchain.modules.is_empty());
+
+        let version = SafeWalletVersion::new(1, 4, 1);
+        let checks = check_onchain_safe(&onchain, None, SAFE, 11155111, &version);
+        assert!(checks.onchain_mismatches.is_empty());
+
+        assert!(read_onchain_safe(&client, SAFE, 1).is_err());
+
+        // Safes before 1.1.0 only have `getModules`
+        let client = RpcClient::new(&fake_node("1.0.0"));
+        let onchain = read_onchain_safe(&client, SAFE, 11155111).unwrap();
+        assert_eq!(onchain.modules, [MODULE]);
+        let version = SafeWalletVersion::new(1, 0, 0);
+        let checks = check_onchain_safe(&onchain, None, SAFE, 11155111, &version);
+        assert!(checks.onchain_mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_check_onchain_safe() {
+        let version = SafeWalletVersion::new(1, 4, 1);
+        let onchain = OnchainSafe {
+            version: version.to_string(),
+            nonce: U256::from(9),
+            threshold: U256::from(1),
+            owners: vec![OWNER],
+            singleton: SINGLETON,
+            guard: Address::ZERO,
+            modules: Vec::new(),
+            domain_separator: DomainHasher::new(version, 1, SAFE).hash(),
+        };
+        let safe_info = SafeInfo {
+            address: SAFE,
+            nonce: 8,
+            threshold: 1,
+            owners: vec![OWNER],
+            master_copy: Some(SINGLETON),
+            modules: Vec::new(),
+            fallback_handler: None,
+            guard: None,
+            version: Some("1.4.1+L2".to_string()),
+        };
+
+        // An older --safe-version hashes the domain without the chain id
+        let old = SafeWalletVersion::new(1, 1, 1);
+        let mismatches =
+            check_onchain_safe(&onchain, Some(&safe_info), SAFE, 1, &old).onchain_mismatches;
+        let fields = mismatches.iter().map(|m| m.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            ["Version", "Nonce", "Domain Separator (matches the on-chain version)",]
+        );
+        assert_eq!(mismatches[0].onchain, "1.4.1");
+        assert_eq!(mismatches[0].expected, "1.1.1");
+        assert_eq!(mismatches[1].source, "API Returned");
+    }
+
+    #[test]
+    #[ignore = "needs a local anvil fork of mainnet, `anvil --fork-url <mainnet rpc>`"]
+    fn test_read_onchain_safe_anvil() {
+        let safe = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+        let client = RpcClient::new("http://127.0.0.1:8545");
+        let onchain = read_onchain_safe(&client, safe, 1).unwrap();
+        assert!(!onchain.owners.is_empty());
+        assert!(onchain.threshold <= U256::from(onchain.owners.len()));
+
+        let version = SafeWalletVersion::parse(&onchain.version).unwrap();
+        let checks = check_onchain_safe(&onchain, None, safe, 1, &version);
+        assert!(checks.onchain_mismatches.is_empty());
+    }
+}
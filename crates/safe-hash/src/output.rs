@@ -1,10 +1,34 @@
+use crate::{
+    addressbook::AddressBook,
+    api::SafeInfo,
+    device::{Device, binary_literal},
+    exec_tx::{ExecutorFees, UnsignedExecutorTx, format_gwei},
+    explain::{Eip712Explanation, Encoding},
+    module::{AllowanceTransferCall, ModuleEntryPoint},
+    multisend::Call,
+    nested::{NestedApproval, NestedHashes},
+    onchain::OnchainSafe,
+    predict::{ProxyCreation, SafeSetup},
+    signer::SignatureStyle,
+    simulate::{
+        BalanceChange, SIMULATION_SPEC, SafeState, SimulatedLog, Simulation, SimulationOutcome,
+    },
+    siwe::SiweMessage,
+    tokens::{TokenCall, TokenInfo, TokenRegistry, format_units},
+    tx_signing::SigningTarget,
+    typed_data::TypedDataSummary,
+};
 use alloy::{
     hex,
-    primitives::{B256, FixedBytes},
+    primitives::{Address, B256, ChainId, FixedBytes, U256, utils::format_ether},
 };
 use cli_table::{Cell, Style, Table};
 use color_print::{cprintln, cstr};
-use safe_utils::EIP7127HashDetails;
+use safe_utils::{
+    ContractSignatureCheck, EIP7127HashDetails, InnerSignatureStatus, OwnerSignature,
+    SignatureKind, get_native_currency,
+};
+use sty::{bold, red, sty, underline};
 
 pub struct SafeHashes {
     pub raw_message_hash: Option<FixedBytes<32>>,
@@ -19,6 +43,150 @@ pub struct Mismatch {
     pub user_value: String,
 }
 
+pub enum ApprovalRisk {
+    Unlimited,
+    AboveThreshold {
+        amount: String,
+        threshold: String,
+    },
+    /// An `--approval-threshold` that cannot be applied, e.g. to a token of unknown decimals
+    ThresholdUnchecked {
+        amount: String,
+        threshold: String,
+        reason: String,
+    },
+    ApprovalForAll,
+}
+
+pub struct RiskyApproval {
+    pub token: Address,
+    pub token_symbol: Option<String>,
+    pub spender: Address,
+    pub spender_label: Option<String>,
+    pub risk: ApprovalRisk,
+}
+
+/// An address of the transaction that closely resembles, but differs from, a known address.
+pub struct LookalikeAddress {
+    pub address: Address,
+    pub known: Address,
+    pub known_as: String,
+}
+
+/// A problem with a Sign-In with Ethereum message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiweIssue {
+    ChainMismatch { message: ChainId, chain_id: ChainId },
+    AddressMismatch { message: Address, safe_address: Address },
+    Expired(String),
+    NotYetValid(String),
+    HostMismatch { domain: String, uri: String },
+}
+
+/// A risk in a well-known EIP-712 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedDataIssue {
+    UnlimitedAmount {
+        token: Address,
+    },
+    /// A deadline more than a year away, formatted as a date
+    FarFutureDeadline(String),
+    UnknownVerifyingContract {
+        primary_type: String,
+        contract: Option<Address>,
+    },
+    /// A `SafeTx` or `SafeMessage` that does not hash the way the Safe does, e.g. because of
+    /// altered types
+    NotSafeHash {
+        primary_type: String,
+        eip712_hash: B256,
+        safe_hash: B256,
+    },
+}
+
+/// An EIP-712 domain that does not match the context it is signed in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainIssue {
+    MissingChainId,
+    ChainMismatch {
+        domain: U256,
+        chain_id: ChainId,
+    },
+    ContractMismatch {
+        domain: Option<Address>,
+        expected: Address,
+    },
+    /// A `SafeTx` or `SafeMessage` that is not verified by the Safe being signed for
+    NotTheSafe {
+        primary_type: String,
+        domain: Option<Address>,
+        safe_address: Address,
+    },
+    /// A domain named after a well-known protocol, verified by none of its deployments
+    ImpersonatedName {
+        name: String,
+        contract: Option<Address>,
+    },
+}
+
+/// Something the simulated execution of a transaction did that needs a closer look.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationIssue {
+    Reverted(String),
+    ExecutionFailure,
+}
+
+/// A transaction nonce that is not the next one the Safe executes, or an API transaction that
+/// changed after it was proposed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonceIssue {
+    Executed {
+        transaction_hash: Option<String>,
+    },
+    /// Below the Safe's nonce, so it can never execute
+    Stale {
+        nonce: u64,
+        current: u64,
+    },
+    /// Above the Safe's nonce, so the transactions in between must execute first
+    Gap {
+        nonce: u64,
+        current: u64,
+    },
+    Modified {
+        submitted: String,
+        modified: String,
+    },
+}
+
+impl NonceIssue {
+    /// Whether the transaction can never execute. Queued transactions above the Safe's nonce are
+    /// signed ahead of time, and edits are only told by the service's timestamps.
+    pub fn blocks_signing(&self) -> bool {
+        matches!(self, Self::Executed { .. } | Self::Stale { .. })
+    }
+}
+
+/// A well-known slot of the Safe's own storage that a transaction changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationChange {
+    /// The slot and what it controls, in plain words
+    pub slot: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// A value read from the Safe contract that differs from the API, the arguments or a computed
+/// hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnchainMismatch {
+    pub field: String,
+    pub onchain: String,
+    /// Where the other value comes from, e.g. `API Returned`
+    pub source: &'static str,
+    pub expected: String,
+}
+
 pub struct SafeWarnings {
     pub zero_address: bool,
     pub zero_value: bool,
@@ -28,6 +196,20 @@ pub struct SafeWarnings {
     pub non_zero_refund_receiver: bool,
     pub argument_mismatches: Vec<Mismatch>,
     pub dangerous_methods: bool,
+    pub unverified_contract: bool,
+    pub invalid_delegate_signature: bool,
+    pub unknown_tokens: Vec<Address>,
+    pub risky_approvals: Vec<RiskyApproval>,
+    pub unknown_counterparties: Vec<Address>,
+    pub address_poisoning: Vec<LookalikeAddress>,
+    pub rejected_contract_signatures: Vec<Address>,
+    pub siwe_issues: Vec<SiweIssue>,
+    pub typed_data_issues: Vec<TypedDataIssue>,
+    pub domain_issues: Vec<DomainIssue>,
+    pub nonce_issues: Vec<NonceIssue>,
+    pub simulation_issues: Vec<SimulationIssue>,
+    pub configuration_changes: Vec<ConfigurationChange>,
+    pub onchain_mismatches: Vec<OnchainMismatch>,
 }
 
 impl SafeWarnings {
@@ -41,9 +223,38 @@ impl SafeWarnings {
             non_zero_refund_receiver: false,
             argument_mismatches: Vec::new(),
             dangerous_methods: false,
+            unverified_contract: false,
+            invalid_delegate_signature: false,
+            unknown_tokens: Vec::new(),
+            risky_approvals: Vec::new(),
+            unknown_counterparties: Vec::new(),
+            address_poisoning: Vec::new(),
+            rejected_contract_signatures: Vec::new(),
+            siwe_issues: Vec::new(),
+            typed_data_issues: Vec::new(),
+            domain_issues: Vec::new(),
+            nonce_issues: Vec::new(),
+            simulation_issues: Vec::new(),
+            configuration_changes: Vec::new(),
+            onchain_mismatches: Vec::new(),
         }
     }
 
+    /// Whether the hash shown may not be what owners mean to sign: it differs from the API or the
+    /// chain, the transaction rewrites the Safe or its nonce can never execute, the
+    /// message or domain targets another app, chain or account, an address imitates a known one,
+    /// or a contract owner rejects its signature.
+    pub fn blocks_signing(&self) -> bool {
+        !self.argument_mismatches.is_empty()
+            || !self.onchain_mismatches.is_empty()
+            || !self.configuration_changes.is_empty()
+            || self.nonce_issues.iter().any(NonceIssue::blocks_signing)
+            || !self.siwe_issues.is_empty()
+            || !self.domain_issues.is_empty()
+            || !self.address_poisoning.is_empty()
+            || !self.rejected_contract_signatures.is_empty()
+    }
+
     pub fn has_warnings(&self) -> bool {
         self.zero_address
             || self.zero_value
@@ -52,6 +263,20 @@ impl SafeWarnings {
             || self.non_zero_gas_token
             || self.non_zero_refund_receiver
             || self.dangerous_methods
+            || self.unverified_contract
+            || self.invalid_delegate_signature
+            || !self.unknown_tokens.is_empty()
+            || !self.risky_approvals.is_empty()
+            || !self.unknown_counterparties.is_empty()
+            || !self.address_poisoning.is_empty()
+            || !self.rejected_contract_signatures.is_empty()
+            || !self.siwe_issues.is_empty()
+            || !self.typed_data_issues.is_empty()
+            || !self.domain_issues.is_empty()
+            || !self.nonce_issues.is_empty()
+            || !self.simulation_issues.is_empty()
+            || !self.configuration_changes.is_empty()
+            || !self.onchain_mismatches.is_empty()
             || !self.argument_mismatches.is_empty()
     }
 
@@ -64,13 +289,31 @@ impl SafeWarnings {
         self.non_zero_gas_token |= other.non_zero_gas_token;
         self.non_zero_refund_receiver |= other.non_zero_refund_receiver;
         self.dangerous_methods |= other.dangerous_methods;
+        self.unverified_contract |= other.unverified_contract;
+        self.invalid_delegate_signature |= other.invalid_delegate_signature;
+        self.unknown_tokens.extend(other.unknown_tokens);
+        self.risky_approvals.extend(other.risky_approvals);
+        self.unknown_counterparties.extend(other.unknown_counterparties);
+        self.address_poisoning.extend(other.address_poisoning);
+        self.rejected_contract_signatures.extend(other.rejected_contract_signatures);
+        self.siwe_issues.extend(other.siwe_issues);
+        self.typed_data_issues.extend(other.typed_data_issues);
+        self.domain_issues.extend(other.domain_issues);
+        self.nonce_issues.extend(other.nonce_issues);
+        self.simulation_issues.extend(other.simulation_issues);
+        self.configuration_changes.extend(other.configuration_changes);
+        self.onchain_mismatches.extend(other.onchain_mismatches);
         self.argument_mismatches.extend(other.argument_mismatches);
     }
 }
 
-pub fn display_api_transaction_details(tx: &crate::api::SafeTransaction) {
-    println!("{:<24} {}", "Safe Address:", tx.safe);
-    println!("{:<24} {}", "To:", tx.to);
+pub fn display_api_transaction_details(
+    tx: &crate::api::SafeTransaction,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!("{:<24} {}", "Safe Address:", book.display(chain_id, tx.safe));
+    println!("{:<24} {}", "To:", book.display(chain_id, tx.to));
     println!("{:<24} {}", "Value:", tx.value);
     println!("{:<24} {}", "Data:", tx.data);
     println!("{:<24} {}", "Operation:", tx.operation);
@@ -82,8 +325,30 @@ pub fn display_api_transaction_details(tx: &crate::api::SafeTransaction) {
     println!("{:<24} {}", "Gas Token:", tx.gas_token);
     println!("{:<24} {}", "Refund Receiver:", tx.refund_receiver);
 
+    println!("{:<24} {}", "Submitted:", tx.submission_date);
+    if tx.is_executed {
+        match tx.is_successful {
+            Some(false) => println!("{:<24} Executed, failed", "Status:"),
+            _ => println!("{:<24} Executed", "Status:"),
+        }
+        if let Some(execution_date) = &tx.execution_date {
+            println!("{:<24} {}", "Executed:", execution_date);
+        }
+        if let Some(transaction_hash) = &tx.transaction_hash {
+            println!("{:<24} {}", "Transaction Hash:", transaction_hash);
+        }
+        if let Some(executor) = tx.executor {
+            println!("{:<24} {}", "Executor:", book.display(chain_id, executor));
+        }
+    } else {
+        println!("{:<24} Pending", "Status:");
+    }
+
     println!("{:<24} {}", "Confirmations Required:", tx.confirmations_required);
     println!("{:<24} {}", "Confirmations Count:", tx.confirmations.len());
+    for confirmation in &tx.confirmations {
+        println!("{:<24} {}", "Confirmed By:", book.display(chain_id, confirmation.owner));
+    }
 
     if let Some(decoded) = &tx.data_decoded {
         println!();
@@ -92,34 +357,453 @@ pub fn display_api_transaction_details(tx: &crate::api::SafeTransaction) {
         println!("{:<12} {}", "Method:", decoded.method);
 
         for param in &decoded.parameters {
-            println!("{:<12} {}: {}", "Parameter:", param.r#type, param.value);
+            let value = match param.value.parse::<Address>() {
+                Ok(address) if param.r#type == "address" => book.display(chain_id, address),
+                _ => param.value.clone(),
+            };
+            println!("{:<12} {}: {}", "Parameter:", param.r#type, value);
+        }
+    }
+}
+
+/// Prints the state a Safe contract reports, read through a JSON-RPC node.
+pub fn display_onchain_safe(onchain: &OnchainSafe, book: &AddressBook, chain_id: ChainId) {
+    println!();
+    cprintln!("<bold>On-chain Safe:</bold>");
+    println!("{:<24} {}", "Version:", onchain.version);
+    println!("{:<24} {}", "Nonce:", onchain.nonce);
+    println!("{:<24} {} of {}", "Threshold:", onchain.threshold, onchain.owners.len());
+    for owner in &onchain.owners {
+        println!("{:<24} {}", "Owner:", book.display(chain_id, *owner));
+    }
+    println!("{:<24} {}", "Singleton:", book.display(chain_id, onchain.singleton));
+    if !onchain.guard.is_zero() {
+        println!("{:<24} {}", "Guard:", book.display(chain_id, onchain.guard));
+    }
+    for module in &onchain.modules {
+        println!("{:<24} {}", "Module:", book.display(chain_id, *module));
+    }
+    println!("{:<24} {}", "Domain Separator:", onchain.domain_separator);
+}
+
+pub fn display_safe_owners(info: &SafeInfo, book: &AddressBook, chain_id: ChainId) {
+    println!("{:<24} {} of {}", "Threshold:", info.threshold, info.owners.len());
+    for owner in &info.owners {
+        println!("{:<24} {}", "Owner:", book.display(chain_id, *owner));
+    }
+}
+
+/// Lists the sub-calls of a MultiSend batch, `calls` being the flattened calls of the transaction.
+pub fn display_multisend_calls(calls: &[Call], book: &AddressBook, chain_id: ChainId) {
+    if calls.len() < 2 {
+        return;
+    }
+    println!();
+    println!("MultiSend Calls:");
+    for (i, call) in calls.iter().enumerate().skip(1) {
+        let operation = if call.operation == 1 { "delegatecall" } else { "call" };
+        println!(
+            "{:<4} {} to {}, value {}, data {}",
+            format!("{}.", i),
+            operation,
+            book.display(chain_id, call.to),
+            call.value,
+            call.data
+        );
+    }
+}
+
+fn format_hash(hash: &B256, device: Option<Device>) -> String {
+    match device {
+        Some(device) => device.format_hash(hash),
+        None => hex::encode(hash),
+    }
+}
+
+fn display_verify_hint(device: Option<Device>) {
+    match device {
+        Some(device) if device.shows_binary_literal() => cprintln!(
+            "<bold>Verify the above binary string literal as the Safe Tx Hash when signing from the {}.</bold>",
+            device
+        ),
+        Some(device) => {
+            cprintln!("<bold>Verify the above values when signing from the {}.</bold>", device)
         }
+        None => cprintln!(
+            "<bold>Verify the above value as the Safe Tx Hash when signing the message from the ledger.</bold>"
+        ),
     }
 }
 
-pub fn display_hashes(hashes: &SafeHashes) {
+/// Prints the hashes, formatted the way `device` shows them when one is given.
+pub fn display_hashes(hashes: &SafeHashes, device: Option<Device>) {
     if let Some(raw_hash) = hashes.raw_message_hash {
-        println!("{:<24} {}", "Raw Message Hash:", hex::encode(raw_hash));
+        println!("{:<24} {}", "Raw Message Hash:", format_hash(&raw_hash, device));
     }
 
-    println!("{:<24} {}", "Domain Hash:", hex::encode(hashes.domain_hash));
-    println!("{:<24} {}", "Message Hash:", hex::encode(hashes.message_hash));
-    println!("{:<24} {}", "Safe Transaction Hash:", hex::encode(hashes.safe_tx_hash));
+    println!("{:<24} {}", "Domain Hash:", format_hash(&hashes.domain_hash, device));
+    println!("{:<24} {}", "Message Hash:", format_hash(&hashes.message_hash, device));
+    println!("{:<24} {}", "Safe Transaction Hash:", format_hash(&hashes.safe_tx_hash, device));
+    if device.is_some_and(|device| device.shows_binary_literal()) {
+        println!("{:<24} {}", "Binary String Literal:", binary_literal(&hashes.safe_tx_hash));
+    }
 
-    cprintln!(
-        "<bold>Verify the above value as the Safe Tx Hash when signing the message from the ledger.</bold>"
-    );
+    display_verify_hint(device);
+}
+
+/// Prints the hashes of every nested Safe as a tree below the Safe of the transaction.
+pub fn display_nested_hashes(
+    safe_address: Address,
+    nested: &[NestedHashes],
+    device: Option<Device>,
+) {
+    println!("Safe {}", safe_address);
+    for (depth, level) in nested.iter().enumerate() {
+        let indent = "   ".repeat(depth);
+        let approval = match level.level.nonce {
+            Some(nonce) => format!("approveHash, nonce {}", nonce),
+            None => "SafeMessage".to_string(),
+        };
+        println!(
+            "{}└─ Safe {} (v{}, {})",
+            indent, level.level.safe_address, level.level.safe_version, approval
+        );
+
+        let indent = format!("{}   ", indent);
+        let hash_label = match level.level.approval {
+            NestedApproval::ApproveHash => "Safe Transaction Hash:",
+            NestedApproval::SafeMessage => "Safe Message Hash:",
+        };
+        println!("{}{:<24} {}", indent, "Approves:", format_hash(&level.approved_hash, device));
+        println!(
+            "{}{:<24} {}",
+            indent,
+            "Domain Hash:",
+            format_hash(&level.hashes.domain_hash, device)
+        );
+        println!(
+            "{}{:<24} {}",
+            indent,
+            "Message Hash:",
+            format_hash(&level.hashes.message_hash, device)
+        );
+        println!(
+            "{}{:<24} {}",
+            indent,
+            hash_label,
+            format_hash(&level.hashes.safe_tx_hash, device)
+        );
+        if device.is_some_and(|device| device.shows_binary_literal()) {
+            println!(
+                "{}{:<24} {}",
+                indent,
+                "Binary String Literal:",
+                binary_literal(&level.hashes.safe_tx_hash)
+            );
+        }
+    }
+    display_verify_hint(device);
 }
 
-pub fn display_message_hashes(hashes: &SafeHashes) {
+/// Prints the exact bytes that are signed, and the text they spell with whitespace escaped.
+pub fn display_message_bytes(message: &[u8]) {
+    println!("{:<24} {} bytes", "Message Length:", message.len());
+    if let Ok(text) = std::str::from_utf8(message) {
+        println!("{:<24} {:?}", "Message Text:", text);
+    }
+    println!("{:<24} {}", "Message Bytes:", hex::encode_prefixed(message));
+}
+
+pub fn display_siwe_message(siwe: &SiweMessage) {
+    println!();
+    cprintln!("<bold>Sign-In with Ethereum:</bold>");
+    println!("{:<24} {}", "Domain:", siwe.domain);
+    println!("{:<24} {}", "Address:", siwe.address);
+    if let Some(statement) = &siwe.statement {
+        println!("{:<24} {}", "Statement:", statement);
+    }
+    println!("{:<24} {}", "URI:", siwe.uri);
+    println!("{:<24} {}", "Chain ID:", siwe.chain_id);
+    println!("{:<24} {}", "Nonce:", siwe.nonce);
+    println!("{:<24} {}", "Issued At:", siwe.issued_at.to_rfc3339());
+    if let Some(expiration_time) = siwe.expiration_time {
+        println!("{:<24} {}", "Expiration Time:", expiration_time.to_rfc3339());
+    }
+    if let Some(not_before) = siwe.not_before {
+        println!("{:<24} {}", "Not Before:", not_before.to_rfc3339());
+    }
+    for resource in &siwe.resources {
+        println!("{:<24} {}", "Resource:", resource);
+    }
+    println!();
+}
+
+pub fn display_message_hashes(hashes: &SafeHashes, device: Option<Device>) {
     if let Some(raw_hash) = hashes.raw_message_hash {
-        println!("{:<24} {}", "Safe Message:", hex::encode(raw_hash));
+        println!("{:<24} {}", "Safe Message:", format_hash(&raw_hash, device));
+    }
+    println!("{:<24} {}", "Safe Message Hash:", format_hash(&hashes.safe_tx_hash, device));
+    println!("{:<24} {}", "Domain Hash:", format_hash(&hashes.domain_hash, device));
+    println!("{:<24} {}", "Message Hash:", format_hash(&hashes.message_hash, device));
+    if device.is_some_and(|device| device.shows_binary_literal()) {
+        println!("{:<24} {}", "Binary String Literal:", binary_literal(&hashes.safe_tx_hash));
+    }
+    display_verify_hint(device);
+}
+
+pub fn display_signature(signer: Address, hash: B256, style: SignatureStyle, signature: &[u8; 65]) {
+    println!();
+    println!("{:<24} {}", "Signer:", signer);
+    println!("{:<24} {}", "Signed Hash:", hash);
+    println!("{:<24} {}", "Signature Style:", style);
+    cprintln!("<bold>{:<24} {}</bold>", "Signature:", hex::encode_prefixed(signature));
+}
+
+pub fn display_combined_signatures(
+    signatures: &[OwnerSignature],
+    threshold: Option<u64>,
+    packed: &[u8],
+    safe_address: Address,
+    calldata: &str,
+) {
+    println!();
+    cprintln!("<bold>Owner Signatures:</bold>");
+    for signature in signatures {
+        let kind = match &signature.kind {
+            SignatureKind::Ecdsa(bytes) if bytes[64] > 30 => "eth_sign".to_string(),
+            SignatureKind::Ecdsa(_) => "EIP-712".to_string(),
+            SignatureKind::ApprovedHash => "approved hash".to_string(),
+            SignatureKind::Contract(bytes) => format!("contract ({} bytes)", bytes.len()),
+        };
+        println!("  {} {}", signature.owner, kind);
     }
-    println!("{:<24} {}", "Safe Message Hash:", hex::encode(hashes.safe_tx_hash));
-    println!("{:<24} {}", "Domain Hash:", hex::encode(hashes.domain_hash));
-    println!("{:<24} {}", "Message Hash:", hex::encode(hashes.message_hash));
-    cprintln!(
-        "<bold>Verify the above value as the Safe Tx Hash when signing the message from the ledger.</bold>"
+    if let Some(threshold) = threshold.filter(|threshold| (signatures.len() as u64) < *threshold) {
+        cprintln!(
+            "<bold><red>Only {} of the {} required signatures, execution will revert.</red></bold>",
+            signatures.len(),
+            threshold
+        );
+    }
+
+    println!("{:<24} {}", "Signatures:", hex::encode_prefixed(packed));
+    println!("{:<24} {}", "Execute On:", safe_address);
+    println!("{:<24} 0x{}", "Execution Calldata:", calldata);
+}
+
+/// Prints the verification of an owner Safe's contract signature as a tree of its signers.
+pub fn display_contract_signature_check(
+    check: &ContractSignatureCheck,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!();
+    cprintln!("<bold>Contract Signature:</bold>");
+    display_contract_signature_level(check, book, chain_id, "");
+}
+
+fn display_contract_signature_level(
+    check: &ContractSignatureCheck,
+    book: &AddressBook,
+    chain_id: ChainId,
+    indent: &str,
+) {
+    let verdict = if check.accepted {
+        cstr!("<green>accepted</green>")
+    } else {
+        cstr!("<red>REJECTED</red>")
+    };
+    println!("{}Safe {}: {}", indent, book.display(chain_id, check.safe), verdict);
+    println!("{}{:<24} {}", indent, "Threshold:", check.threshold);
+    println!("{}{:<24} {}", indent, "Safe Message Hash:", check.message_hash);
+    if !check.sorted {
+        println!("{}Signatures within the threshold are not sorted by owner", indent);
+    }
+    for signature in &check.signatures {
+        let status = match signature.status {
+            InnerSignatureStatus::Valid => "valid",
+            InnerSignatureStatus::NotAnOwner => "not an owner",
+            InnerSignatureStatus::Unverifiable => "unverifiable",
+            InnerSignatureStatus::Rejected => "rejected",
+        };
+        println!("{}└─ {} {}", indent, book.display(chain_id, signature.signature.owner), status);
+        if let Some(nested) = &signature.nested {
+            display_contract_signature_level(nested, book, chain_id, &format!("{}   ", indent));
+        }
+    }
+}
+
+pub fn display_executor_tx(
+    target: &SigningTarget,
+    signatures: &[OwnerSignature],
+    chain_id: ChainId,
+    nonce: u64,
+    gas_limit: u64,
+    fees: ExecutorFees,
+    unsigned: &UnsignedExecutorTx,
+) {
+    let tx_data = target.tx_data.as_ref().expect("only transactions are executed");
+    let operation = if tx_data.operation == 1 { "DELEGATECALL" } else { "CALL" };
+
+    println!();
+    cprintln!("<bold>Executor Transaction:</bold>");
+    println!(
+        "  Executes Safe {} nonce {}: {} to {} with {} wei and {} bytes of data",
+        target.safe_address,
+        target.nonce,
+        operation,
+        tx_data.to,
+        tx_data.value,
+        hex::decode(&tx_data.data).map(|data| data.len()).unwrap_or_default()
+    );
+    println!("  Signed by {} owner(s)", signatures.len());
+    if let Some(threshold) =
+        target.threshold.filter(|threshold| (signatures.len() as u64) < *threshold)
+    {
+        cprintln!(
+            "<bold><red>  Only {} of the {} required signatures, execution will revert.</red></bold>",
+            signatures.len(),
+            threshold
+        );
+    }
+
+    match fees {
+        ExecutorFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+            println!("{:<24} EIP-1559", "Type:");
+            println!("{:<24} {}", "Max Fee Per Gas:", format_gwei(max_fee_per_gas));
+            println!("{:<24} {}", "Max Priority Fee:", format_gwei(max_priority_fee_per_gas));
+        }
+        ExecutorFees::Legacy { gas_price } => {
+            println!("{:<24} Legacy", "Type:");
+            println!("{:<24} {}", "Gas Price:", format_gwei(gas_price));
+        }
+    }
+    println!("{:<24} {}", "Chain ID:", chain_id);
+    println!("{:<24} {}", "Nonce:", nonce);
+    println!("{:<24} {}", "To:", target.safe_address);
+    println!("{:<24} {}", "Gas Limit:", gas_limit);
+    println!(
+        "{:<24} {} {}",
+        "Max Cost:",
+        format_ether(U256::from(gas_limit) * U256::from(fees.max_gas_price())),
+        get_native_currency(chain_id)
+    );
+    println!("{:<24} {}", "Unsigned Transaction:", unsigned.encoded);
+    cprintln!("<bold>{:<24} {}</bold>", "Signing Hash:", unsigned.signing_hash);
+}
+
+/// Prints a call a module makes the Safe execute, with its Delay queue hash where one applies.
+pub fn display_module_transaction(
+    entry_point: &ModuleEntryPoint,
+    call: &Call,
+    delay_hash: Option<B256>,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!("{:<24} {}", "Entry Point:", entry_point.method());
+    if let ModuleEntryPoint::WithRole { role_key, should_revert, .. } = entry_point {
+        println!("{:<24} {}", "Role Key:", role_key);
+        println!("{:<24} {}", "Should Revert:", should_revert);
+    }
+    println!("{:<24} {}", "To:", book.display(chain_id, call.to));
+    println!("{:<24} {}", "Value:", call.value);
+    println!("{:<24} {}", "Data:", call.data);
+    println!("{:<24} {}", "Operation:", if call.operation == 1 { "delegatecall" } else { "call" });
+    if let Some(delay_hash) = delay_hash {
+        cprintln!("<bold>{:<24} {}</bold>", "Delay Transaction Hash:", delay_hash);
+    }
+}
+
+/// Prints an AllowanceModule transfer with the hash its delegate signs, when it can be computed.
+pub fn display_allowance_transfer(
+    transfer: &AllowanceTransferCall,
+    transfer_hash: Option<B256>,
+    signer: Option<Address>,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!("{:<24} executeAllowanceTransfer", "Entry Point:");
+    println!("{:<24} {}", "Safe:", book.display(chain_id, transfer.safe));
+    println!("{:<24} {}", "Token:", book.display(chain_id, transfer.token));
+    println!("{:<24} {}", "To:", book.display(chain_id, transfer.to));
+    println!("{:<24} {}", "Amount:", transfer.amount);
+    println!("{:<24} {}", "Payment Token:", book.display(chain_id, transfer.payment_token));
+    println!("{:<24} {}", "Payment:", transfer.payment);
+    println!("{:<24} {}", "Delegate:", book.display(chain_id, transfer.delegate));
+    match transfer_hash {
+        Some(hash) => cprintln!("<bold>{:<24} {}</bold>", "Transfer Hash:", hash),
+        None => println!(
+            "{:<24} pass --module-address and --allowance-nonce to compute it",
+            "Transfer Hash:"
+        ),
+    }
+    if let Some(signer) = signer {
+        println!("{:<24} {}", "Signed By:", book.display(chain_id, signer));
+    }
+}
+
+/// Prints the parameters of a Safe deployment, with its `setup` arguments when the initializer
+/// is a `setup` call.
+pub fn display_proxy_creation(creation: &ProxyCreation, setup: Option<&SafeSetup>) {
+    println!("{:<24} {}", "Singleton:", creation.singleton);
+    println!("{:<24} {}", "Salt Nonce:", creation.salt_nonce);
+    if creation.chain_specific {
+        println!("{:<24} createChainSpecificProxyWithNonce", "Factory Method:");
+    }
+    let Some(setup) = setup else {
+        cprintln!(
+            "<bold><red>The initializer is not a setup call:</red></bold> {}",
+            hex::encode_prefixed(&creation.initializer)
+        );
+        return;
+    };
+
+    println!("{:<24} {} of {}", "Threshold:", setup.threshold, setup.owners.len());
+    for owner in &setup.owners {
+        println!("{:<24} {}", "Owner:", owner);
+    }
+    println!("{:<24} {}", "Fallback Handler:", setup.fallback_handler);
+    if setup.to != Address::ZERO {
+        println!("{:<24} {}", "Setup Delegatecall:", setup.to);
+        println!("{:<24} {}", "Setup Data:", hex::encode_prefixed(&setup.data));
+    }
+    if setup.payment != U256::ZERO {
+        println!(
+            "{:<24} {} of {} to {}",
+            "Deployment Payment:", setup.payment, setup.payment_token, setup.payment_receiver
+        );
+    }
+    if let Err(e) = setup.validate() {
+        cprintln!("<bold><red>setup would revert: {}</red></bold>", e);
+    }
+    println!("{:<24} {}", "Initializer:", hex::encode_prefixed(&creation.initializer));
+}
+
+pub fn display_predicted_address(salt: B256, address: Option<Address>, zksync: bool) {
+    println!("{:<24} {}", "Salt:", salt);
+    match address {
+        Some(address) => cprintln!("<bold>{:<24} {}</bold>", "Safe Address:", address),
+        None if zksync => println!(
+            "{:<24} pass --factory and --proxy-bytecode-hash to predict it",
+            "Safe Address:"
+        ),
+        None => println!(
+            "{:<24} pass --factory and --proxy-creation-code to predict it",
+            "Safe Address:"
+        ),
+    }
+}
+
+pub fn display_token_call(
+    call: &TokenCall,
+    token: Option<&TokenInfo>,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!();
+    println!(
+        "{:<24} {}",
+        "Token Call:",
+        call.describe(token, |address| book.display(chain_id, address))
     );
 }
 
@@ -157,17 +841,218 @@ pub fn display_warnings(warnings: &SafeWarnings) {
                 "• Transaction data matches a function signature that modifies the owners or threshold of the Safe."
             );
         }
+        if warnings.unverified_contract {
+            cprintln!(
+                "• Transaction calls a contract that is not verified by any verification provider"
+            );
+        }
+        if warnings.invalid_delegate_signature {
+            cprintln!("• The allowance transfer is not signed by its delegate");
+        }
+        for token in &warnings.unknown_tokens {
+            cprintln!("• Token contract {} is not on any token list", token);
+        }
+        for approval in &warnings.risky_approvals {
+            let token = match &approval.token_symbol {
+                Some(symbol) => format!("{} ({})", symbol, approval.token),
+                None => approval.token.to_string(),
+            };
+            let spender = match &approval.spender_label {
+                Some(label) => format!("{} [address book: {}]", approval.spender, label),
+                None => format!("{} [not in address book]", approval.spender),
+            };
+            match &approval.risk {
+                ApprovalRisk::Unlimited => {
+                    cprintln!(
+                        "• <bold>Unlimited approval</bold> of {} to spender {}",
+                        token,
+                        spender
+                    )
+                }
+                ApprovalRisk::AboveThreshold { amount, threshold } => cprintln!(
+                    "• Approval of {} {} exceeds the {} threshold, spender {}",
+                    amount,
+                    token,
+                    threshold,
+                    spender
+                ),
+                ApprovalRisk::ThresholdUnchecked { amount, threshold, reason } => cprintln!(
+                    "• Approval of {} raw units of {} <bold>could not be checked</bold> against the {} threshold, {}, spender {}",
+                    amount,
+                    token,
+                    threshold,
+                    reason,
+                    spender
+                ),
+                ApprovalRisk::ApprovalForAll => cprintln!(
+                    "• <bold>setApprovalForAll</bold> on {} gives operator {} control of every token in the collection",
+                    token,
+                    spender
+                ),
+            }
+        }
+        for counterparty in &warnings.unknown_counterparties {
+            cprintln!("• Unknown counterparty {} is not in the address book", counterparty);
+        }
+        for owner in &warnings.rejected_contract_signatures {
+            cprintln!("• Contract signature of owner Safe {} would be rejected", owner);
+        }
+        for issue in &warnings.siwe_issues {
+            match issue {
+                SiweIssue::ChainMismatch { message, chain_id } => {
+                    cprintln!("• Sign-in message is for chain {}, not chain {}", message, chain_id)
+                }
+                SiweIssue::AddressMismatch { message, safe_address } => {
+                    cprintln!("• Sign-in message is for {}, not the Safe {}", message, safe_address)
+                }
+                SiweIssue::Expired(time) => {
+                    cprintln!("• Sign-in message expired at {}", time)
+                }
+                SiweIssue::NotYetValid(time) => {
+                    cprintln!("• Sign-in message is not valid before {}", time)
+                }
+                SiweIssue::HostMismatch { domain, uri } => cprintln!(
+                    "• Sign-in request from <bold>{}</bold> sends you to <bold>{}</bold>, a different host",
+                    domain,
+                    uri
+                ),
+            }
+        }
+        for issue in &warnings.typed_data_issues {
+            match issue {
+                TypedDataIssue::UnlimitedAmount { token } => {
+                    cprintln!(
+                        "• Typed message grants an <bold>unlimited</bold> amount of {}",
+                        token
+                    )
+                }
+                TypedDataIssue::FarFutureDeadline(deadline) => {
+                    cprintln!(
+                        "• Typed message stays valid until {}, more than a year away",
+                        deadline
+                    )
+                }
+                TypedDataIssue::UnknownVerifyingContract {
+                    primary_type,
+                    contract: Some(contract),
+                } => {
+                    cprintln!(
+                        "• {} message is verified by unknown contract {}",
+                        primary_type,
+                        contract
+                    )
+                }
+                TypedDataIssue::NotSafeHash { primary_type, eip712_hash, safe_hash } => {
+                    cprintln!(
+                        "• {} hashes to <bold>{}</bold>, but the Safe computes {} for it",
+                        primary_type,
+                        eip712_hash,
+                        safe_hash
+                    )
+                }
+                TypedDataIssue::UnknownVerifyingContract { primary_type, contract: None } => {
+                    cprintln!("• {} message has no verifying contract", primary_type)
+                }
+            }
+        }
+        for issue in &warnings.domain_issues {
+            let contract = |contract: &Option<Address>| {
+                contract
+                    .map_or("no verifying contract".to_string(), |contract| contract.to_string())
+            };
+            match issue {
+                DomainIssue::MissingChainId => {
+                    cprintln!(
+                        "• Typed message domain has <bold>no chain id</bold>, it can be replayed on any chain"
+                    )
+                }
+                DomainIssue::ChainMismatch { domain, chain_id } => {
+                    cprintln!(
+                        "• Typed message is for chain <bold>{}</bold>, not chain {}",
+                        domain,
+                        chain_id
+                    )
+                }
+                DomainIssue::ContractMismatch { domain, expected } => cprintln!(
+                    "• Typed message is verified by <bold>{}</bold>, not the expected {}",
+                    contract(domain),
+                    expected
+                ),
+                DomainIssue::NotTheSafe { primary_type, domain, safe_address } => cprintln!(
+                    "• {} is verified by <bold>{}</bold>, not the Safe {}",
+                    primary_type,
+                    contract(domain),
+                    safe_address
+                ),
+                DomainIssue::ImpersonatedName { name, contract: verifying_contract } => cprintln!(
+                    "• Domain <bold>{:?}</bold> is verified by {}, which is not a known {} deployment",
+                    name,
+                    contract(verifying_contract),
+                    name
+                ),
+            }
+        }
+        for issue in &warnings.nonce_issues {
+            match issue {
+                NonceIssue::Executed { transaction_hash } => cprintln!(
+                    "• Transaction was <bold>already executed</bold> in {}",
+                    transaction_hash.as_deref().unwrap_or("an unknown transaction")
+                ),
+                NonceIssue::Stale { nonce, current } => cprintln!(
+                    "• Nonce {} is <bold>below the Safe's nonce {}</bold>, the transaction can never execute",
+                    nonce,
+                    current
+                ),
+                NonceIssue::Gap { nonce, current } => cprintln!(
+                    "• Nonce {} is <bold>ahead of the Safe's nonce {}</bold>, transactions {} to {} must execute first",
+                    nonce,
+                    current,
+                    current,
+                    nonce - 1
+                ),
+                NonceIssue::Modified { submitted, modified } => cprintln!(
+                    "• Transaction was <bold>modified</bold> at {} after it was submitted at {}",
+                    modified,
+                    submitted
+                ),
+            }
+        }
+        for issue in &warnings.simulation_issues {
+            match issue {
+                SimulationIssue::Reverted(reason) => {
+                    cprintln!("• Simulated transaction <bold>reverts</bold>: {}", reason)
+                }
+                SimulationIssue::ExecutionFailure => cprintln!(
+                    "• Simulated transaction <bold>fails</bold>, but still uses the nonce"
+                ),
+            }
+        }
+        // Display changes to the Safe's own configuration prominently
+        if !warnings.configuration_changes.is_empty() {
+            cprintln!("<bold><red>🚨 SAFE CONFIGURATION CHANGES:</red></bold>");
+            for change in &warnings.configuration_changes {
+                cprintln!("  <bold>{}</bold>", change.slot);
+                println!("  {:<20} {}", "Before:", change.before);
+                println!("  {:<20} {}", "After:", change.after);
+            }
+        }
+        // Display possible address poisoning prominently
+        for lookalike in &warnings.address_poisoning {
+            cprintln!("<bold><red>🚨 POSSIBLE ADDRESS POISONING:</red></bold>");
+            let (address, known) = highlight_differences(lookalike.address, lookalike.known);
+            println!("  {:<20} {}", "In transaction:", address);
+            println!("  {:<20} {} ({})", "Looks like:", known, lookalike.known_as);
+        }
         // Display argument mismatches prominently
         if !warnings.argument_mismatches.is_empty() {
             cprintln!("<bold><red>🚨 ARGUMENT MISMATCHES:</red></bold>");
 
             for mismatch in &warnings.argument_mismatches {
                 // Parse the mismatch message to extract API and user values
-                let mut mismatch_rows = Vec::new();
-                mismatch_rows
-                    .push(vec!["API Returned".cell(), mismatch.api_value.to_string().cell()]);
-                mismatch_rows
-                    .push(vec!["User Supplied".cell(), mismatch.user_value.to_string().cell()]);
+                let mismatch_rows = vec![
+                    vec!["API Returned".cell(), mismatch.api_value.to_string().cell()],
+                    vec!["User Supplied".cell(), mismatch.user_value.to_string().cell()],
+                ];
                 let mismatch_table = mismatch_rows
                     .table()
                     .title(vec![
@@ -179,6 +1064,26 @@ pub fn display_warnings(warnings: &SafeWarnings) {
             }
         }
 
+        // Display on-chain mismatches like argument mismatches
+        if !warnings.onchain_mismatches.is_empty() {
+            cprintln!("<bold><red>🚨 ON-CHAIN MISMATCHES:</red></bold>");
+
+            for mismatch in &warnings.onchain_mismatches {
+                let mismatch_rows = vec![
+                    vec!["On-chain".cell(), mismatch.onchain.clone().cell()],
+                    vec![mismatch.source.cell(), mismatch.expected.clone().cell()],
+                ];
+                let mismatch_table = mismatch_rows
+                    .table()
+                    .title(vec![
+                        cstr!("").cell().bold(true),
+                        mismatch.field.clone().cell().bold(true),
+                    ])
+                    .bold(true);
+                println!("{}", mismatch_table.display().unwrap());
+            }
+        }
+
         println!(); // Add spacing after warnings
         cprintln!(
             "<bold><red>Please review the above warnings before signing the transaction.</red></bold>"
@@ -186,6 +1091,207 @@ pub fn display_warnings(warnings: &SafeWarnings) {
     }
 }
 
+/// Renders both addresses with the characters that differ between them highlighted.
+fn highlight_differences(a: Address, b: Address) -> (String, String) {
+    let (a, b) = (a.to_checksum(None), b.to_checksum(None));
+    let mut highlighted_a = String::new();
+    let mut highlighted_b = String::new();
+    for (ca, cb) in a.chars().zip(b.chars()) {
+        if ca.eq_ignore_ascii_case(&cb) {
+            highlighted_a.push(ca);
+            highlighted_b.push(cb);
+        } else {
+            highlighted_a.push_str(&sty!(ca, [red, bold, underline]));
+            highlighted_b.push_str(&sty!(cb, [red, bold, underline]));
+        }
+    }
+    (highlighted_a, highlighted_b)
+}
+
+fn display_encoding(encoding: &Encoding) {
+    println!();
+    cprintln!("<bold>{}</bold>", encoding.path);
+    if let (Some(encoded_type), Some(type_hash)) = (&encoding.encoded_type, encoding.type_hash) {
+        println!("  {:<22} {}", "Encoded Type:", encoded_type);
+        println!("  {:<22} {}", "Typehash:", type_hash);
+    }
+    for member in &encoding.members {
+        println!("  {:<22} {} ({})", format!("{}:", member.name), member.word, member.type_name);
+    }
+    let hashed = if encoding.type_hash.is_some() { "Struct Hash:" } else { "Array Hash:" };
+    println!("  {:<22} {}", hashed, encoding.hash);
+}
+
+/// Prints the version dependent choices, then the encoding of the domain and of every struct and
+/// array of the message, then how they combine into the digest.
+pub fn display_eip712_explanation(explanation: &Eip712Explanation) {
+    println!();
+    cprintln!("<bold>EIP-712 Encoding:</bold>");
+    for note in &explanation.notes {
+        println!("• {}", note);
+    }
+    display_encoding(&explanation.domain);
+    for encoding in &explanation.message {
+        display_encoding(encoding);
+    }
+    println!();
+    println!("{:<24} {}", "Domain Separator:", explanation.domain_separator);
+    println!("{:<24} {}", "Struct Hash:", explanation.struct_hash);
+    println!("{:<24} {}", "Digest:", explanation.digest);
+    println!("{:<24} keccak256(0x1901 ‖ domain separator ‖ struct hash)", "");
+}
+
+fn display_event_parameters(log: &SimulatedLog, book: &AddressBook, chain_id: ChainId) {
+    let Some(event) = log.event() else {
+        for topic in log.topics.iter().skip(1) {
+            println!("       {:<17} {}", "Topic:", topic);
+        }
+        if !log.data.is_empty() {
+            println!("       {:<17} {}", "Data:", log.data);
+        }
+        return;
+    };
+    let types = event.trim_end_matches(')').split_once('(').map_or("", |(_, types)| types);
+    for (type_name, word) in types.split(',').zip(log.words()) {
+        let value = match type_name {
+            "address" => book.display(chain_id, Address::from_word(word)),
+            "uint256" => U256::from_be_bytes(word.0).to_string(),
+            "bool" => (!word.is_zero()).to_string(),
+            _ => word.to_string(),
+        };
+        println!("       {:<17} {}", format!("{}:", type_name), value);
+    }
+}
+
+fn format_balance_change(
+    change: &BalanceChange,
+    tokens: &TokenRegistry,
+    chain_id: ChainId,
+) -> String {
+    let sign = if change.delta.is_negative() { "-" } else { "+" };
+    let amount = change.delta.unsigned_abs();
+    match change.token {
+        None => format!("{}{} {}", sign, format_ether(amount), get_native_currency(chain_id)),
+        Some(token) => match tokens.get(token) {
+            Some(info) => {
+                format!("{}{} {}", sign, format_units(amount, info.decimals), info.symbol)
+            }
+            None => format!("{}{} of token {}", sign, amount, token),
+        },
+    }
+}
+
+fn display_list_changes(
+    label: &str,
+    before: &[Address],
+    after: &[Address],
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    for added in after.iter().filter(|entry| !before.contains(entry)) {
+        println!("{:<24} {}", format!("Added {}:", label), book.display(chain_id, *added));
+    }
+    for removed in before.iter().filter(|entry| !after.contains(entry)) {
+        println!("{:<24} {}", format!("Removed {}:", label), book.display(chain_id, *removed));
+    }
+}
+
+/// Prints the outcome of a simulated transaction, its events, balance changes and changes to the
+/// Safe.
+pub fn display_simulation(
+    simulation: &Simulation,
+    tokens: &TokenRegistry,
+    book: &AddressBook,
+    chain_id: ChainId,
+) {
+    println!();
+    cprintln!("<bold>Simulation:</bold>");
+    println!("{:<24} {}", "Block:", simulation.block);
+    println!("{:<24} {:?}, assumed for every chain", "EVM Rules:", SIMULATION_SPEC);
+    match &simulation.outcome {
+        SimulationOutcome::Success => cprintln!("{:<24} <bold>Success</bold>", "Result:"),
+        SimulationOutcome::ExecutionFailure => {
+            cprintln!("{:<24} <bold><red>Execution failure</red></bold>", "Result:")
+        }
+        SimulationOutcome::Reverted(reason) => {
+            cprintln!("{:<24} <bold><red>Reverted</red></bold> ({})", "Result:", reason)
+        }
+        SimulationOutcome::Halted(reason) => {
+            cprintln!("{:<24} <bold><red>Halted</red></bold> ({})", "Result:", reason)
+        }
+    }
+    println!("{:<24} {}", "Gas Used:", simulation.gas_used);
+
+    if !simulation.logs.is_empty() {
+        println!();
+        println!("Events:");
+        for (i, log) in simulation.logs.iter().enumerate() {
+            let event = log.event().map_or_else(
+                || log.topics.first().map_or("anonymous".to_string(), |topic| topic.to_string()),
+                str::to_string,
+            );
+            println!(
+                "{:<4} {} from {}",
+                format!("{}.", i + 1),
+                event,
+                book.display(chain_id, log.address)
+            );
+            display_event_parameters(log, book, chain_id);
+        }
+    }
+
+    if !simulation.balance_changes.is_empty() {
+        println!();
+        println!("Balance Changes:");
+        for change in &simulation.balance_changes {
+            println!(
+                "{:<24} {}",
+                format_balance_change(change, tokens, chain_id),
+                book.display(chain_id, change.holder)
+            );
+        }
+    }
+
+    let (before, after) = (&simulation.safe_before, &simulation.safe_after);
+    let nonce_changed = after.nonce != simulation.expected_nonce();
+    let setup_changed = SafeState { nonce: before.nonce, ..after.clone() } != *before;
+    if setup_changed || nonce_changed || !simulation.other_storage_changes.is_empty() {
+        println!();
+        println!("Safe Changes:");
+        if before.singleton != after.singleton {
+            println!("{:<24} {} -> {}", "Singleton:", before.singleton, after.singleton);
+        }
+        display_list_changes("Owner", &before.owners, &after.owners, book, chain_id);
+        if before.owner_count != after.owner_count {
+            println!("{:<24} {} -> {}", "Owner Count:", before.owner_count, after.owner_count);
+        }
+        if before.threshold != after.threshold {
+            println!("{:<24} {} -> {}", "Threshold:", before.threshold, after.threshold);
+        }
+        if nonce_changed {
+            println!("{:<24} {} instead of {}", "Nonce:", after.nonce, simulation.expected_nonce());
+        }
+        display_list_changes("Module", &before.modules, &after.modules, book, chain_id);
+        if before.guard != after.guard {
+            println!("{:<24} {} -> {}", "Guard:", before.guard, after.guard);
+        }
+        if before.fallback_handler != after.fallback_handler {
+            println!(
+                "{:<24} {} -> {}",
+                "Fallback Handler:", before.fallback_handler, after.fallback_handler
+            );
+        }
+        for change in &simulation.other_storage_changes {
+            println!("{:<24} {:#x}", "Storage Slot:", change.slot);
+            println!("{:<24} {:#x} -> {:#x}", "", change.before, change.after);
+        }
+    }
+}
+
+pub fn display_typed_data_summary(summary: &TypedDataSummary, tokens: &TokenRegistry) {
+    cprintln!("<bold>{:<24} {}</bold>", "Typed Message:", summary.describe(tokens));
+}
+
 pub fn display_eip712_hash(hash: &EIP7127HashDetails) {
     println!("{:<24} {}", "EIP 712 Hash:", hash.eip_712_hash.clone());
     println!("{:<24} {}", "Domain Hash:", hash.domain_hash.clone());
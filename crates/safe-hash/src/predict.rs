@@ -0,0 +1,278 @@
+use alloy::{
+    primitives::{Address, B256, Bytes, ChainId, U256, keccak256},
+    sol,
+    sol_types::{SolCall, SolValue},
+};
+
+sol! {
+    function setup(
+        address[] _owners,
+        uint256 _threshold,
+        address to,
+        bytes data,
+        address fallbackHandler,
+        address paymentToken,
+        uint256 payment,
+        address paymentReceiver
+    );
+    function createProxyWithNonce(address _singleton, bytes initializer, uint256 saltNonce);
+    function createChainSpecificProxyWithNonce(
+        address _singleton,
+        bytes initializer,
+        uint256 saltNonce
+    );
+}
+
+/// Head of the owners linked list in Safe storage, which cannot be an owner.
+pub const SENTINEL_OWNERS: Address =
+    Address::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+/// zkSync Era, whose CREATE2 derivation differs from the EVM one.
+pub const ZKSYNC_CHAIN_ID: ChainId = 324;
+
+/// Arguments of a Safe's `setup` call, the initializer of its proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeSetup {
+    pub owners: Vec<Address>,
+    pub threshold: U256,
+    /// Contract delegatecalled during setup, usually to enable modules
+    pub to: Address,
+    pub data: Bytes,
+    pub fallback_handler: Address,
+    pub payment_token: Address,
+    pub payment: U256,
+    pub payment_receiver: Address,
+}
+
+impl SafeSetup {
+    pub fn encode(&self) -> Bytes {
+        setupCall {
+            _owners: self.owners.clone(),
+            _threshold: self.threshold,
+            to: self.to,
+            data: self.data.clone(),
+            fallbackHandler: self.fallback_handler,
+            paymentToken: self.payment_token,
+            payment: self.payment,
+            paymentReceiver: self.payment_receiver,
+        }
+        .abi_encode()
+        .into()
+    }
+
+    /// Checks the owners and threshold the way `setup` does, which reverts when they are invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold > U256::from(self.owners.len()) {
+            return Err(format!(
+                "threshold {} exceeds the {} owners",
+                self.threshold,
+                self.owners.len()
+            ));
+        }
+        if self.threshold.is_zero() {
+            return Err("threshold must be at least 1".to_string());
+        }
+        for (i, owner) in self.owners.iter().enumerate() {
+            if *owner == Address::ZERO || *owner == SENTINEL_OWNERS {
+                return Err(format!("{} cannot be an owner", owner));
+            }
+            if self.owners[..i].contains(owner) {
+                return Err(format!("owner {} is listed twice", owner));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn decode(initializer: &[u8]) -> Option<Self> {
+        if initializer.get(..4)? != setupCall::SELECTOR {
+            return None;
+        }
+        let call = setupCall::abi_decode(initializer, true).ok()?;
+        Some(Self {
+            owners: call._owners,
+            threshold: call._threshold,
+            to: call.to,
+            data: call.data,
+            fallback_handler: call.fallbackHandler,
+            payment_token: call.paymentToken,
+            payment: call.payment,
+            payment_receiver: call.paymentReceiver,
+        })
+    }
+}
+
+/// A proxy factory call deploying a Safe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyCreation {
+    pub singleton: Address,
+    pub initializer: Bytes,
+    pub salt_nonce: U256,
+    /// `createChainSpecificProxyWithNonce` (v1.4.1), which mixes the chain id into the salt
+    pub chain_specific: bool,
+}
+
+impl ProxyCreation {
+    /// Decodes `createProxyWithNonce` or `createChainSpecificProxyWithNonce` calldata.
+    pub fn decode(calldata: &[u8]) -> Option<Self> {
+        let (singleton, initializer, salt_nonce, chain_specific) =
+            match calldata.get(..4)?.try_into().ok()? {
+                createProxyWithNonceCall::SELECTOR => {
+                    let call = createProxyWithNonceCall::abi_decode(calldata, true).ok()?;
+                    (call._singleton, call.initializer, call.saltNonce, false)
+                }
+                createChainSpecificProxyWithNonceCall::SELECTOR => {
+                    let call =
+                        createChainSpecificProxyWithNonceCall::abi_decode(calldata, true).ok()?;
+                    (call._singleton, call.initializer, call.saltNonce, true)
+                }
+                _ => return None,
+            };
+        Some(Self { singleton, initializer, salt_nonce, chain_specific })
+    }
+
+    /// The CREATE2 salt the factory derives: `keccak256(keccak256(initializer) | saltNonce)`,
+    /// followed by the chain id for chain specific proxies.
+    pub fn salt(&self, chain_id: ChainId) -> B256 {
+        let initializer_hash = keccak256(&self.initializer);
+        if self.chain_specific {
+            keccak256((initializer_hash, self.salt_nonce, U256::from(chain_id)).abi_encode_packed())
+        } else {
+            keccak256((initializer_hash, self.salt_nonce).abi_encode_packed())
+        }
+    }
+}
+
+/// Address of a proxy deployed with CREATE2 by `factory`, the deployment data being the factory's
+/// `proxyCreationCode()` followed by the singleton address as a 32-byte word.
+pub fn proxy_address(
+    factory: Address,
+    salt: B256,
+    proxy_creation_code: &[u8],
+    singleton: Address,
+) -> Address {
+    let deployment_data = [proxy_creation_code, singleton.into_word().as_slice()].concat();
+    factory.create2_from_code(salt, deployment_data)
+}
+
+/// Address of a proxy deployed by `factory` on zkSync, where CREATE2 hashes
+/// `keccak256("zksyncCreate2") | factory | salt | bytecode hash | keccak256(constructor input)`.
+pub fn zksync_proxy_address(
+    factory: Address,
+    salt: B256,
+    proxy_bytecode_hash: B256,
+    singleton: Address,
+) -> Address {
+    let preimage = [
+        keccak256("zksyncCreate2").as_slice(),
+        factory.into_word().as_slice(),
+        salt.as_slice(),
+        proxy_bytecode_hash.as_slice(),
+        keccak256(singleton.into_word()).as_slice(),
+    ]
+    .concat();
+    Address::from_word(keccak256(preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{hex, primitives::address};
+
+    const FACTORY: Address = address!("0x4e1DCf7AD4e460CfD30791CCC4F9c8a4f820ec67");
+    const SINGLETON: Address = address!("0x29fcB43b46531BcA003ddC8FCB67FFE91900C762");
+    const OWNER: Address = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+
+    fn setup() -> SafeSetup {
+        SafeSetup {
+            owners: vec![OWNER, address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1")],
+            threshold: U256::from(2),
+            to: Address::ZERO,
+            data: Bytes::new(),
+            fallback_handler: address!("0xfd0732Dc9E303f09fCEf3a7388Ad10A83459Ec99"),
+            payment_token: Address::ZERO,
+            payment: U256::ZERO,
+            payment_receiver: Address::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_setup_round_trip() {
+        let initializer = setup().encode();
+        assert_eq!(hex::encode(&initializer[..4]), "b63e800d");
+        assert_eq!(SafeSetup::decode(&initializer), Some(setup()));
+        assert_eq!(SafeSetup::decode(&[0xb6, 0x3e]), None);
+    }
+
+    #[test]
+    fn test_validate_setup() {
+        assert_eq!(setup().validate(), Ok(()));
+        assert!(SafeSetup { threshold: U256::from(3), ..setup() }.validate().is_err());
+        assert!(SafeSetup { threshold: U256::ZERO, ..setup() }.validate().is_err());
+        assert!(SafeSetup { owners: vec![OWNER, OWNER], ..setup() }.validate().is_err());
+        assert!(
+            SafeSetup { owners: vec![Address::ZERO], threshold: U256::from(1), ..setup() }
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_decode_proxy_creation() {
+        let calldata = createProxyWithNonceCall {
+            _singleton: SINGLETON,
+            initializer: setup().encode(),
+            saltNonce: U256::from(7),
+        }
+        .abi_encode();
+        let creation = ProxyCreation::decode(&calldata).unwrap();
+
+        assert_eq!(creation.singleton, SINGLETON);
+        assert_eq!(SafeSetup::decode(&creation.initializer), Some(setup()));
+        assert!(!creation.chain_specific);
+
+        let mut preimage = keccak256(&creation.initializer).to_vec();
+        preimage.extend_from_slice(&U256::from(7).to_be_bytes::<32>());
+        assert_eq!(creation.salt(1), keccak256(&preimage));
+
+        let chain_specific = ProxyCreation { chain_specific: true, ..creation };
+        preimage.extend_from_slice(&U256::from(1).to_be_bytes::<32>());
+        assert_eq!(chain_specific.salt(1), keccak256(&preimage));
+    }
+
+    #[test]
+    fn test_proxy_address() {
+        let salt = B256::repeat_byte(0x11);
+        let creation_code = [0x60, 0x80, 0x60, 0x40];
+        let init_code_hash = keccak256(
+            hex::decode("6080604000000000000000000000000029fcb43b46531bca003ddc8fcb67ffe91900c762")
+                .unwrap(),
+        );
+        let mut preimage = vec![0xff];
+        preimage.extend_from_slice(FACTORY.as_slice());
+        preimage.extend_from_slice(salt.as_slice());
+        preimage.extend_from_slice(init_code_hash.as_slice());
+
+        assert_eq!(
+            proxy_address(FACTORY, salt, &creation_code, SINGLETON),
+            Address::from_slice(&keccak256(&preimage)[12..])
+        );
+    }
+
+    #[test]
+    fn test_zksync_proxy_address() {
+        let salt = B256::repeat_byte(0x11);
+        let bytecode_hash = B256::repeat_byte(0x01);
+        let mut preimage =
+            hex::decode("2020dba91b30cc0006188af794c2fb30dd8520db7e2c088b7fc7c103c00ca494")
+                .unwrap();
+        preimage.extend_from_slice(&[0; 12]);
+        preimage.extend_from_slice(FACTORY.as_slice());
+        preimage.extend_from_slice(salt.as_slice());
+        preimage.extend_from_slice(bytecode_hash.as_slice());
+        preimage.extend_from_slice(keccak256(SINGLETON.into_word()).as_slice());
+
+        let address = zksync_proxy_address(FACTORY, salt, bytecode_hash, SINGLETON);
+        assert_eq!(address, Address::from_slice(&keccak256(&preimage)[12..]));
+        assert_ne!(address, proxy_address(FACTORY, salt, bytecode_hash.as_slice(), SINGLETON));
+    }
+}
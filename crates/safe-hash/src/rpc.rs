@@ -0,0 +1,210 @@
+use crate::typed_data::{TypedDataInput, typed_data_from_request};
+use alloy::{
+    hex,
+    primitives::{Address, Bytes, ChainId, U256},
+};
+use serde_json::Value;
+
+/// A signing request a dapp sends to a Safe, e.g. through WalletConnect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcRequest {
+    /// `eth_sendTransaction`, which the Safe turns into a transaction proposal
+    SendTransaction { from: Address, to: Address, value: U256, data: String },
+    /// `personal_sign`, signed as a Safe message
+    PersonalSign { address: Address, message: Bytes },
+    /// `eth_signTypedData_v4`, whose EIP-712 hash is signed as a Safe message
+    SignTypedData(TypedDataInput),
+}
+
+impl RpcRequest {
+    pub fn method(&self) -> &'static str {
+        match self {
+            RpcRequest::SendTransaction { .. } => "eth_sendTransaction",
+            RpcRequest::PersonalSign { .. } => "personal_sign",
+            RpcRequest::SignTypedData(_) => "eth_signTypedData_v4",
+        }
+    }
+}
+
+/// A request with the chain of the WalletConnect session it came from, when wrapped in one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningRequest {
+    pub chain_id: Option<ChainId>,
+    pub request: RpcRequest,
+}
+
+fn address_param(value: &Value) -> Option<Address> {
+    value.as_str()?.parse().ok()
+}
+
+/// Parses a JSON-RPC request, or a WalletConnect `session_request` whose `params` hold the
+/// request and its `eip155:<chain id>` chain.
+pub fn parse_rpc_request(input: &str) -> Result<SigningRequest, String> {
+    let value: Value =
+        serde_json::from_str(input).map_err(|e| format!("Invalid JSON-RPC request: {}", e))?;
+    let (request, chain_id) = match value.pointer("/params/request") {
+        Some(request) => {
+            let chain_id = value
+                .pointer("/params/chainId")
+                .and_then(Value::as_str)
+                .map(|chain| {
+                    chain
+                        .strip_prefix("eip155:")
+                        .and_then(|id| id.parse().ok())
+                        .ok_or_else(|| format!("Unsupported WalletConnect chain {:?}", chain))
+                })
+                .transpose()?;
+            (request, chain_id)
+        }
+        None => (&value, None),
+    };
+
+    let method = request.get("method").and_then(Value::as_str).ok_or("Missing request method")?;
+    let params = request
+        .get("params")
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("Missing {} params", method))?;
+    let request = match method {
+        "eth_sendTransaction" => {
+            let tx = params.first().ok_or("Missing eth_sendTransaction transaction")?;
+            let from = tx.get("from").and_then(address_param).ok_or("Missing transaction from")?;
+            let to = tx
+                .get("to")
+                .and_then(address_param)
+                .ok_or("Missing transaction to, a Safe cannot deploy a contract directly")?;
+            let value = match tx.get("value") {
+                Some(Value::String(value)) => {
+                    value.parse().map_err(|e| format!("Invalid value {}: {}", value, e))?
+                }
+                Some(Value::Number(value)) => value
+                    .as_u64()
+                    .map(U256::from)
+                    .ok_or_else(|| format!("Invalid value {}, pass it as a hex string", value))?,
+                Some(value) => return Err(format!("Invalid value {}", value)),
+                None => U256::ZERO,
+            };
+            let data = tx.get("data").or_else(|| tx.get("input")).and_then(Value::as_str);
+            let data = data.unwrap_or("0x").to_string();
+            hex::decode(&data).map_err(|e| format!("Invalid transaction data: {}", e))?;
+            RpcRequest::SendTransaction { from, to, value, data }
+        }
+        "personal_sign" => {
+            let [message, address] = <&[Value; 2]>::try_from(params.as_slice())
+                .map_err(|_| "personal_sign params must be the message and the address")?;
+            // Some dapps send the address first
+            let (message, address) = match (address_param(message), address_param(address)) {
+                (Some(address), None) => (params[1].as_str(), address),
+                (_, Some(address)) => (message.as_str(), address),
+                (None, None) => return Err("Missing personal_sign address".to_string()),
+            };
+            let message = message.ok_or("personal_sign message must be a string")?;
+            let message = match message.strip_prefix("0x").map(hex::decode) {
+                Some(Ok(bytes)) => bytes.into(),
+                _ => Bytes::copy_from_slice(message.as_bytes()),
+            };
+            RpcRequest::PersonalSign { address, message }
+        }
+        "eth_signTypedData_v4" => RpcRequest::SignTypedData(typed_data_from_request(request)?),
+        method => return Err(format!("Unsupported signing method {}", method)),
+    };
+
+    Ok(SigningRequest { chain_id, request })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use serde_json::json;
+
+    const SAFE: Address = address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1");
+
+    #[test]
+    fn test_parse_send_transaction() {
+        let request = json!({
+            "id": 1730000000000000u64,
+            "topic": "5c0a9b1e",
+            "params": {
+                "request": {
+                    "method": "eth_sendTransaction",
+                    "params": [{
+                        "from": SAFE,
+                        "to": "0xdd13E55209Fd76AfE204dBda4007C227904f0a81",
+                        "value": "0x38d7ea4c68000",
+                        "data": "0x"
+                    }]
+                },
+                "chainId": "eip155:11155111"
+            }
+        });
+
+        assert_eq!(
+            parse_rpc_request(&request.to_string()),
+            Ok(SigningRequest {
+                chain_id: Some(11155111),
+                request: RpcRequest::SendTransaction {
+                    from: SAFE,
+                    to: address!("0xdd13E55209Fd76AfE204dBda4007C227904f0a81"),
+                    value: U256::from(1_000_000_000_000_000u64),
+                    data: "0x".to_string(),
+                },
+            })
+        );
+
+        let numeric = json!({
+            "method": "eth_sendTransaction",
+            "params": [{"from": SAFE, "to": SAFE, "value": 1_000_000_000_000_000u64}]
+        });
+        assert!(matches!(
+            parse_rpc_request(&numeric.to_string()).unwrap().request,
+            RpcRequest::SendTransaction { value, .. } if value == U256::from(1_000_000_000_000_000u64)
+        ));
+        for value in [json!(null), json!(true), json!(1.5), json!({"hex": "0x1"})] {
+            let request = json!({
+                "method": "eth_sendTransaction",
+                "params": [{"from": SAFE, "to": SAFE, "value": value}]
+            });
+            assert!(parse_rpc_request(&request.to_string()).is_err());
+        }
+
+        let creation =
+            json!({"method": "eth_sendTransaction", "params": [{"from": SAFE, "data": "0x60"}]});
+        assert!(parse_rpc_request(&creation.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_personal_sign() {
+        // "Hello" hex encoded, in both parameter orders
+        for params in [json!(["0x48656c6c6f", SAFE]), json!([SAFE, "0x48656c6c6f"])] {
+            let request =
+                json!({"jsonrpc": "2.0", "id": 1, "method": "personal_sign", "params": params});
+            assert_eq!(
+                parse_rpc_request(&request.to_string()).unwrap().request,
+                RpcRequest::PersonalSign { address: SAFE, message: Bytes::from_static(b"Hello") }
+            );
+        }
+
+        let text = json!({"method": "personal_sign", "params": ["Sign in", SAFE]});
+        assert_eq!(parse_rpc_request(&text.to_string()).unwrap(), SigningRequest {
+            chain_id: None,
+            request: RpcRequest::PersonalSign {
+                address: SAFE,
+                message: Bytes::from_static(b"Sign in"),
+            },
+        });
+    }
+
+    #[test]
+    fn test_parse_sign_typed_data() {
+        let typed_data = std::fs::read_to_string("../../test/another_example.json").unwrap();
+        let request =
+            json!({"method": "eth_signTypedData_v4", "params": [SAFE, typed_data.clone()]});
+        let SigningRequest { request, .. } = parse_rpc_request(&request.to_string()).unwrap();
+
+        assert_eq!(
+            request,
+            RpcRequest::SignTypedData(TypedDataInput { json: typed_data, signer: Some(SAFE) })
+        );
+        assert!(parse_rpc_request(r#"{"method": "eth_sign", "params": []}"#).is_err());
+    }
+}
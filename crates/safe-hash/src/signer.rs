@@ -0,0 +1,224 @@
+use alloy::{
+    primitives::B256,
+    signers::{
+        SignerSync,
+        local::{MnemonicBuilder, PrivateKeySigner, coins_bip39::English},
+    },
+};
+use clap::{Args, ValueEnum};
+use std::{error::Error, fmt, fs, path::PathBuf};
+
+/// Environment variable holding the keystore password, checked before prompting.
+pub const PASSWORD_ENV: &str = "SAFE_HASH_PASSWORD";
+
+/// Environment variable holding the optional BIP39 passphrase of the mnemonic.
+pub const MNEMONIC_PASSPHRASE_ENV: &str = "SAFE_HASH_MNEMONIC_PASSPHRASE";
+
+/// How the Safe hash is signed, which decides the `v` value Safe contracts expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SignatureStyle {
+    /// Sign the hash itself (`v` is 27 or 28)
+    Eip712,
+    /// Sign the hash as an `eth_sign` personal message (`v` is 31 or 32)
+    EthSign,
+}
+
+impl fmt::Display for SignatureStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureStyle::Eip712 => write!(f, "EIP-712"),
+            SignatureStyle::EthSign => write!(f, "eth_sign"),
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SignerArgs {
+    /// Encrypted JSON keystore file of the signing owner
+    #[arg(long, global = true, conflicts_with_all = ["mnemonic", "mnemonic_file"])]
+    pub keystore: Option<PathBuf>,
+
+    /// Prompt for the mnemonic of the signing owner
+    #[arg(long, global = true, conflicts_with = "mnemonic_file")]
+    pub mnemonic: bool,
+
+    /// File containing the mnemonic of the signing owner
+    #[arg(long, global = true)]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// Derivation path of the key when signing with a mnemonic
+    #[arg(long, global = true, default_value = "m/44'/60'/0'/0/0")]
+    pub hd_path: String,
+
+    /// File containing the keystore password. Defaults to the SAFE_HASH_PASSWORD environment
+    /// variable, then to a prompt
+    #[arg(long, global = true)]
+    pub password_file: Option<PathBuf>,
+
+    /// File containing the BIP39 passphrase of the mnemonic. Defaults to the
+    /// SAFE_HASH_MNEMONIC_PASSPHRASE environment variable, then to no passphrase
+    #[arg(long, global = true, conflicts_with = "keystore")]
+    pub mnemonic_passphrase_file: Option<PathBuf>,
+
+    /// Signature style
+    #[arg(long, global = true, default_value = "eip712")]
+    pub style: SignatureStyle,
+}
+
+fn read_secret_file(path: &PathBuf) -> Result<String, Box<dyn Error>> {
+    let secret = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Returns the secret from `file`, the `env` environment variable or, when `prompt` is set, the
+/// terminal.
+fn secret(
+    file: Option<&PathBuf>,
+    env: &str,
+    prompt: Option<&str>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    if let Some(path) = file {
+        return read_secret_file(path).map(Some);
+    }
+    if let Ok(secret) = std::env::var(env) {
+        return Ok(Some(secret));
+    }
+    match prompt {
+        Some(prompt) => Ok(Some(rpassword::prompt_password(prompt)?)),
+        None => Ok(None),
+    }
+}
+
+/// Loads the signing key from a keystore or mnemonic, never from the command line.
+pub fn load_signer(args: &SignerArgs) -> Result<PrivateKeySigner, Box<dyn Error>> {
+    if let Some(keystore) = &args.keystore {
+        let password =
+            secret(args.password_file.as_ref(), PASSWORD_ENV, Some("Keystore password: "))?
+                .unwrap_or_default();
+        return PrivateKeySigner::decrypt_keystore(keystore, password).map_err(|e| {
+            format!("Failed to decrypt keystore {}: {}", keystore.display(), e).into()
+        });
+    }
+
+    let phrase = match &args.mnemonic_file {
+        Some(path) => read_secret_file(path)?,
+        None if args.mnemonic => rpassword::prompt_password("Mnemonic: ")?,
+        None => return Err("pass --keystore, --mnemonic or --mnemonic-file to sign".into()),
+    };
+    let mut builder = MnemonicBuilder::<English>::default()
+        .phrase(phrase.trim())
+        .derivation_path(&args.hd_path)
+        .map_err(|e| format!("Invalid derivation path {:?}: {}", args.hd_path, e))?;
+    let passphrase = secret(args.mnemonic_passphrase_file.as_ref(), MNEMONIC_PASSPHRASE_ENV, None)?;
+    if let Some(passphrase) = passphrase {
+        builder = builder.password(passphrase);
+    }
+    Ok(builder.build().map_err(|e| format!("Invalid mnemonic: {}", e))?)
+}
+
+/// Signs a Safe hash, returning the 65-byte `r | s | v` signature Safe contracts verify.
+pub fn sign_safe_hash(
+    signer: &PrivateKeySigner,
+    hash: B256,
+    style: SignatureStyle,
+) -> Result<[u8; 65], Box<dyn Error>> {
+    let signature = match style {
+        SignatureStyle::Eip712 => signer.sign_hash_sync(&hash)?.as_bytes(),
+        SignatureStyle::EthSign => {
+            let mut signature = signer.sign_message_sync(hash.as_slice())?.as_bytes();
+            // Safe tells eth_sign signatures apart by `v` being offset by 4
+            signature[64] += 4;
+            signature
+        }
+    };
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{PrimitiveSignature, address, b256, eip191_hash_message};
+
+    const HASH: B256 = b256!("0xad06b099fca34e51e4886643d95d9a19ace2cd024065efb66662a876e8c40343");
+
+    fn args() -> SignerArgs {
+        SignerArgs {
+            keystore: None,
+            mnemonic: false,
+            mnemonic_file: None,
+            hd_path: "m/44'/60'/0'/0/0".to_string(),
+            password_file: None,
+            mnemonic_passphrase_file: None,
+            style: SignatureStyle::Eip712,
+        }
+    }
+
+    #[test]
+    fn test_sign_safe_hash() {
+        let signer = PrivateKeySigner::random();
+
+        let signature = sign_safe_hash(&signer, HASH, SignatureStyle::Eip712).unwrap();
+        assert!(matches!(signature[64], 27 | 28));
+        let recovered = PrimitiveSignature::try_from(&signature[..]).unwrap();
+        assert_eq!(recovered.recover_address_from_prehash(&HASH).unwrap(), signer.address());
+
+        let mut signature = sign_safe_hash(&signer, HASH, SignatureStyle::EthSign).unwrap();
+        assert!(matches!(signature[64], 31 | 32));
+        signature[64] -= 4;
+        let recovered = PrimitiveSignature::try_from(&signature[..]).unwrap();
+        let prefixed = eip191_hash_message(HASH);
+        assert_eq!(recovered.recover_address_from_prehash(&prefixed).unwrap(), signer.address());
+    }
+
+    #[test]
+    fn test_load_signer_from_mnemonic_file() {
+        let path =
+            std::env::temp_dir().join(format!("safe-hash-mnemonic-{}.txt", std::process::id()));
+        fs::write(&path, "test test test test test test test test test test test junk\n").unwrap();
+
+        let signer = load_signer(&SignerArgs { mnemonic_file: Some(path.clone()), ..args() });
+        assert_eq!(
+            signer.unwrap().address(),
+            address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+
+        // A passphrase derives a different account
+        let passphrase = path.with_extension("passphrase");
+        fs::write(&passphrase, "hunter2\n").unwrap();
+        let signer = load_signer(&SignerArgs {
+            mnemonic_file: Some(path.clone()),
+            mnemonic_passphrase_file: Some(passphrase.clone()),
+            ..args()
+        });
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&passphrase).unwrap();
+        assert_ne!(
+            signer.unwrap().address(),
+            address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+    }
+
+    #[test]
+    fn test_load_signer_from_keystore() {
+        let dir = std::env::temp_dir().join(format!("safe-hash-keystore-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let password_file = dir.join("password");
+        fs::write(&password_file, "hunter2\n").unwrap();
+        let (expected, name) = PrivateKeySigner::new_keystore(
+            &dir,
+            &mut alloy::signers::k256::elliptic_curve::rand_core::OsRng,
+            "hunter2",
+            None,
+        )
+        .unwrap();
+
+        let signer = load_signer(&SignerArgs {
+            keystore: Some(dir.join(name)),
+            password_file: Some(password_file),
+            ..args()
+        });
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(signer.unwrap().address(), expected.address());
+    }
+}
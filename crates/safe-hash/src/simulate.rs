@@ -0,0 +1,637 @@
+use crate::{
+    onchain::RpcClient,
+    output::{SafeWarnings, SimulationIssue},
+    predict::SENTINEL_OWNERS,
+    tx_signing::TxInput,
+};
+use alloy::{
+    primitives::{Address, B256, Bytes, ChainId, I256, U64, U256, keccak256},
+    sol,
+    sol_types::{Revert, SolCall, SolError},
+};
+use revm::{
+    Evm,
+    db::{CacheDB, DatabaseRef},
+    primitives::{self as evm, AccountInfo, Bytecode, ExecutionResult, SpecId, TxKind},
+};
+use safe_utils::{FullTx, OwnerSignature, pack_signatures};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+sol! {
+    function execTransaction(
+        address to,
+        uint256 value,
+        bytes data,
+        uint8 operation,
+        uint256 safeTxGas,
+        uint256 baseGas,
+        uint256 gasPrice,
+        address gasToken,
+        address refundReceiver,
+        bytes signatures
+    ) returns (bool success);
+}
+
+/// EVM rules every simulation runs with, whatever hardfork the chain is on.
+pub const SIMULATION_SPEC: SpecId = SpecId::CANCUN;
+
+/// Owner added to the Safe for the simulation, executing with an approved hash signature.
+const SIMULATION_OWNER: Address = Address::new([0x5a; 20]);
+
+// Storage layout shared by every Safe version
+pub const SINGLETON_SLOT: U256 = U256::ZERO;
+const MODULES_SLOT: U256 = U256::from_limbs([1, 0, 0, 0]);
+const OWNERS_SLOT: U256 = U256::from_limbs([2, 0, 0, 0]);
+const OWNER_COUNT_SLOT: U256 = U256::from_limbs([3, 0, 0, 0]);
+const THRESHOLD_SLOT: U256 = U256::from_limbs([4, 0, 0, 0]);
+const NONCE_SLOT: U256 = U256::from_limbs([5, 0, 0, 0]);
+
+/// Events worth naming in a simulation, all with static parameters.
+const KNOWN_EVENTS: &[&str] = &[
+    "Transfer(address,address,uint256)",
+    "Approval(address,address,uint256)",
+    "ApprovalForAll(address,address,bool)",
+    "Deposit(address,uint256)",
+    "Withdrawal(address,uint256)",
+    "ExecutionSuccess(bytes32,uint256)",
+    "ExecutionFailure(bytes32,uint256)",
+    "SafeReceived(address,uint256)",
+    "AddedOwner(address)",
+    "RemovedOwner(address)",
+    "ChangedThreshold(uint256)",
+    "EnabledModule(address)",
+    "DisabledModule(address)",
+    "ChangedGuard(address)",
+    "ChangedFallbackHandler(address)",
+    "ChangedMasterCopy(address)",
+    "SignMsg(bytes32)",
+];
+
+pub fn guard_slot() -> U256 {
+    keccak256("guard_manager.guard.address").into()
+}
+
+fn fallback_handler_slot() -> U256 {
+    keccak256("fallback_manager.handler.address").into()
+}
+
+/// Slot of `key` in a Solidity `mapping(address => address)` stored at `slot`.
+fn mapping_slot(key: Address, slot: U256) -> U256 {
+    keccak256([key.into_word().0, slot.to_be_bytes()].concat()).into()
+}
+
+fn slot_address(value: U256) -> Address {
+    Address::from_word(value.into())
+}
+
+fn to_evm_address(address: Address) -> evm::Address {
+    evm::Address::new(address.into_array())
+}
+
+fn from_evm_address(address: evm::Address) -> Address {
+    Address::new(address.into_array())
+}
+
+/// Owners, threshold and the other setup of a Safe, read from its storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeState {
+    pub singleton: Address,
+    pub owners: Vec<Address>,
+    pub owner_count: U256,
+    pub threshold: U256,
+    pub nonce: U256,
+    pub modules: Vec<Address>,
+    pub guard: Address,
+    pub fallback_handler: Address,
+}
+
+impl SafeState {
+    fn read(storage: impl Fn(U256) -> Result<U256, String>) -> Result<Self, String> {
+        Ok(Self {
+            singleton: slot_address(storage(SINGLETON_SLOT)?),
+            owners: linked_list(&storage, OWNERS_SLOT)?,
+            owner_count: storage(OWNER_COUNT_SLOT)?,
+            threshold: storage(THRESHOLD_SLOT)?,
+            nonce: storage(NONCE_SLOT)?,
+            modules: linked_list(&storage, MODULES_SLOT)?,
+            guard: slot_address(storage(guard_slot())?),
+            fallback_handler: slot_address(storage(fallback_handler_slot())?),
+        })
+    }
+
+    /// Storage slots holding this setup, owner and module entries included.
+    fn slots(&self) -> Vec<U256> {
+        let mut slots = vec![
+            SINGLETON_SLOT,
+            OWNER_COUNT_SLOT,
+            THRESHOLD_SLOT,
+            NONCE_SLOT,
+            guard_slot(),
+            fallback_handler_slot(),
+        ];
+        for owner in self.owners.iter().chain([&SENTINEL_OWNERS, &SIMULATION_OWNER]) {
+            slots.push(mapping_slot(*owner, OWNERS_SLOT));
+        }
+        for module in self.modules.iter().chain([&SENTINEL_OWNERS]) {
+            slots.push(mapping_slot(*module, MODULES_SLOT));
+        }
+        slots
+    }
+}
+
+/// Walks a linked list of owners or modules, which starts and ends at the sentinel.
+fn linked_list(
+    storage: impl Fn(U256) -> Result<U256, String>,
+    slot: U256,
+) -> Result<Vec<Address>, String> {
+    let mut entries = Vec::new();
+    let mut current = SENTINEL_OWNERS;
+    loop {
+        let next = slot_address(storage(mapping_slot(current, slot))?);
+        if next == SENTINEL_OWNERS || next == Address::ZERO {
+            return Ok(entries);
+        }
+        if entries.len() > 1000 {
+            return Err(format!("linked list at slot {} does not end", slot));
+        }
+        entries.push(next);
+        current = next;
+    }
+}
+
+/// How the simulated `execTransaction` ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationOutcome {
+    Success,
+    /// `execTransaction` returned false: the call failed but the nonce was used, which Safes before
+    /// 1.3.0 or with a `safeTxGas` or `gasPrice` allow
+    ExecutionFailure,
+    Reverted(String),
+    Halted(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+impl SimulatedLog {
+    /// Signature of the event when it is a known one.
+    pub fn event(&self) -> Option<&'static str> {
+        let topic = self.topics.first()?;
+        KNOWN_EVENTS.iter().copied().find(|event| keccak256(event) == *topic)
+    }
+
+    /// The 32-byte words of the parameters, indexed ones first, which is the declaration order for
+    /// every known event.
+    pub fn words(&self) -> Vec<B256> {
+        let data = self.data.chunks_exact(32).map(B256::from_slice);
+        self.topics.iter().skip(1).copied().chain(data).collect()
+    }
+}
+
+/// Change of the ETH balance, or of an ERC-20 balance derived from `Transfer` events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub holder: Address,
+    /// `None` for ETH
+    pub token: Option<Address>,
+    pub delta: I256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageChange {
+    pub slot: U256,
+    pub before: U256,
+    pub after: U256,
+}
+
+/// What a Safe transaction does when executed on top of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub block: u64,
+    /// Nonce the transaction is executed with, whatever the Safe's current nonce
+    pub nonce: u64,
+    pub outcome: SimulationOutcome,
+    pub gas_used: u64,
+    pub logs: Vec<SimulatedLog>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub safe_before: SafeState,
+    pub safe_after: SafeState,
+    /// Changes to Safe storage outside of its setup, e.g. by a delegatecall
+    pub other_storage_changes: Vec<StorageChange>,
+}
+
+impl Simulation {
+    /// Nonce of the Safe after the transaction, when it leaves the rest of the storage alone.
+    pub fn expected_nonce(&self) -> U256 {
+        match self.outcome {
+            SimulationOutcome::Success | SimulationOutcome::ExecutionFailure => {
+                U256::from(self.nonce) + U256::from(1)
+            }
+            SimulationOutcome::Reverted(_) | SimulationOutcome::Halted(_) => self.safe_before.nonce,
+        }
+    }
+}
+
+/// The block a simulation runs on top of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationBlock {
+    pub chain_id: ChainId,
+    pub number: u64,
+    pub timestamp: u64,
+    pub gas_limit: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcBlock {
+    number: U64,
+    timestamp: U64,
+    gas_limit: U64,
+    hash: B256,
+}
+
+/// Account and storage reads from a JSON-RPC node, pinned to one block.
+pub struct RpcDb {
+    client: RpcClient,
+    pub block: SimulationBlock,
+}
+
+impl RpcDb {
+    /// Connects to the node and pins its latest block.
+    pub fn latest(url: &str) -> Result<Self, String> {
+        let client = RpcClient::new(url);
+        let chain_id = client.chain_id()?;
+        let block: RpcBlock = client.request("eth_getBlockByNumber", json!(["latest", false]))?;
+        let block = SimulationBlock {
+            chain_id,
+            number: block.number.to(),
+            timestamp: block.timestamp.to(),
+            gas_limit: block.gas_limit.to(),
+        };
+        Ok(Self { client, block })
+    }
+
+    fn block_tag(&self) -> String {
+        format!("{:#x}", self.block.number)
+    }
+}
+
+impl DatabaseRef for RpcDb {
+    type Error = String;
+
+    fn basic_ref(&self, address: evm::Address) -> Result<Option<AccountInfo>, String> {
+        let address = from_evm_address(address);
+        let params = json!([address, self.block_tag()]);
+        let balance: U256 = self.client.request("eth_getBalance", params.clone())?;
+        let nonce: U64 = self.client.request("eth_getTransactionCount", params.clone())?;
+        let code: Bytes = self.client.request("eth_getCode", params)?;
+        let code = Bytecode::new_raw(evm::Bytes::copy_from_slice(&code));
+        Ok(Some(AccountInfo::new(balance, nonce.to(), code.hash_slow(), code)))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: evm::B256) -> Result<Bytecode, String> {
+        // Accounts are loaded with their code
+        Err(format!("code {} was not loaded with its account", code_hash))
+    }
+
+    fn storage_ref(&self, address: evm::Address, index: U256) -> Result<U256, String> {
+        let params = json!([from_evm_address(address), index, self.block_tag()]);
+        let value: B256 = self.client.request("eth_getStorageAt", params)?;
+        Ok(value.into())
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<evm::B256, String> {
+        let block: RpcBlock = self
+            .client
+            .request("eth_getBlockByNumber", json!([format!("{:#x}", number), false]))?;
+        Ok(evm::B256::new(block.hash.0))
+    }
+}
+
+fn revert_reason(output: &[u8]) -> String {
+    match Revert::abi_decode(output, true) {
+        Ok(revert) => revert.reason,
+        Err(_) if output.is_empty() => "no reason".to_string(),
+        Err(_) => alloy::hex::encode_prefixed(output),
+    }
+}
+
+/// ETH changes of the touched accounts, then ERC-20 changes summed from `Transfer` events, the
+/// Safe's first.
+fn balance_changes(
+    eth: Vec<(Address, U256, U256)>,
+    logs: &[SimulatedLog],
+    safe_address: Address,
+) -> Vec<BalanceChange> {
+    let mut deltas = BTreeMap::<(Option<Address>, Address), I256>::new();
+    for (holder, before, after) in eth {
+        let delta = I256::from_raw(after).wrapping_sub(I256::from_raw(before));
+        *deltas.entry((None, holder)).or_default() += delta;
+    }
+    let transfer = keccak256("Transfer(address,address,uint256)");
+    for log in logs {
+        // ERC-721 transfers index the token id as well
+        if log.topics.len() != 3 || log.topics[0] != transfer || log.data.len() != 32 {
+            continue;
+        }
+        let amount = I256::from_raw(U256::from_be_slice(&log.data));
+        let (from, to) = (Address::from_word(log.topics[1]), Address::from_word(log.topics[2]));
+        *deltas.entry((Some(log.address), from)).or_default() -= amount;
+        *deltas.entry((Some(log.address), to)).or_default() += amount;
+    }
+
+    let mut changes = deltas
+        .into_iter()
+        .filter(|(_, delta)| !delta.is_zero())
+        .map(|((token, holder), delta)| BalanceChange { holder, token, delta })
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|change| change.holder != safe_address);
+    changes
+}
+
+/// Executes a Safe transaction on top of the state in `db`. The Safe gets an extra owner and a
+/// threshold of one, so the transaction runs without its owners' signatures.
+pub fn simulate<DB: DatabaseRef<Error = String>>(
+    db: DB,
+    block: &SimulationBlock,
+    tx: &TxInput,
+    safe_address: Address,
+    nonce: u64,
+) -> Result<Simulation, String> {
+    let safe = to_evm_address(safe_address);
+    let code = db.basic_ref(safe)?.and_then(|account| account.code).unwrap_or_default();
+    if code.is_empty() {
+        return Err(format!("{} has no code at block {}", safe_address, block.number));
+    }
+    let safe_before = SafeState::read(|slot| db.storage_ref(safe, slot))?;
+
+    let signatures = pack_signatures(vec![OwnerSignature::approved_hash(SIMULATION_OWNER)])
+        .map_err(|e| e.to_string())?;
+    let calldata = FullTx::new(
+        tx.to,
+        tx.value,
+        tx.data.clone(),
+        tx.operation,
+        tx.safe_tx_gas,
+        tx.base_gas(),
+        tx.gas_price,
+        tx.gas_token,
+        tx.refund_receiver,
+        U256::from(nonce),
+        alloy::hex::encode(&signatures),
+    )
+    .calldata();
+    let calldata = alloy::hex::decode(calldata).map_err(|e| e.to_string())?;
+
+    let mut cache = CacheDB::new(db);
+    let sentinel = U256::from_be_slice(SENTINEL_OWNERS.as_slice());
+    cache.insert_account_storage(safe, mapping_slot(SIMULATION_OWNER, OWNERS_SLOT), sentinel)?;
+    cache.insert_account_storage(safe, THRESHOLD_SLOT, U256::from(1))?;
+    // The Safe hashes the transaction with its stored nonce
+    cache.insert_account_storage(safe, NONCE_SLOT, U256::from(nonce))?;
+
+    let mut evm = Evm::builder()
+        .with_db(cache)
+        .with_spec_id(SIMULATION_SPEC)
+        .modify_cfg_env(|cfg| cfg.chain_id = block.chain_id)
+        .modify_block_env(|env| {
+            env.number = U256::from(block.number);
+            env.timestamp = U256::from(block.timestamp);
+            env.gas_limit = U256::from(block.gas_limit);
+            env.basefee = U256::ZERO;
+            env.prevrandao = Some(evm::B256::ZERO);
+        })
+        .modify_tx_env(|env| {
+            env.caller = to_evm_address(SIMULATION_OWNER);
+            env.gas_limit = block.gas_limit;
+            env.gas_price = U256::ZERO;
+            env.transact_to = TxKind::Call(safe);
+            env.value = U256::ZERO;
+            env.data = calldata.into();
+            env.nonce = None;
+        })
+        .build();
+    let result = evm.transact().map_err(|e| e.to_string())?;
+    let cache = evm.db();
+
+    let (outcome, gas_used, logs) = match result.result {
+        ExecutionResult::Success { gas_used, logs, output, .. } => {
+            let success = execTransactionCall::abi_decode_returns(output.data(), true)
+                .is_ok_and(|returns| returns.success);
+            let outcome = if success {
+                SimulationOutcome::Success
+            } else {
+                SimulationOutcome::ExecutionFailure
+            };
+            (outcome, gas_used, logs)
+        }
+        ExecutionResult::Revert { gas_used, output } => {
+            (SimulationOutcome::Reverted(revert_reason(&output)), gas_used, Vec::new())
+        }
+        ExecutionResult::Halt { reason, gas_used } => {
+            (SimulationOutcome::Halted(format!("{:?}", reason)), gas_used, Vec::new())
+        }
+    };
+    let logs = logs
+        .into_iter()
+        .map(|log| SimulatedLog {
+            address: from_evm_address(log.address),
+            topics: log.data.topics().iter().map(|topic| B256::new(topic.0)).collect(),
+            data: Bytes::copy_from_slice(&log.data.data),
+        })
+        .collect::<Vec<_>>();
+
+    let mut eth = Vec::new();
+    for (address, account) in &result.state {
+        if !account.is_touched() || *address == to_evm_address(SIMULATION_OWNER) {
+            continue;
+        }
+        let before = cache.db.basic_ref(*address)?.map(|info| info.balance).unwrap_or_default();
+        eth.push((from_evm_address(*address), before, account.info.balance));
+    }
+    let balance_changes = balance_changes(eth, &logs, safe_address);
+
+    let changed = result
+        .state
+        .get(&safe)
+        .map(|account| {
+            account
+                .changed_storage_slots()
+                .map(|(slot, value)| (*slot, value.present_value()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    // Slots the transaction did not write keep their value from before the overrides
+    let safe_after = SafeState::read(|slot| match changed.get(&slot) {
+        Some(value) => Ok(*value),
+        None => cache.db.storage_ref(safe, slot),
+    })?;
+
+    let known_slots = [safe_before.slots(), safe_after.slots()].concat();
+    let mut other_storage_changes = Vec::new();
+    for (slot, after) in &changed {
+        if known_slots.contains(slot) {
+            continue;
+        }
+        let before = cache.db.storage_ref(safe, *slot)?;
+        if before != *after {
+            other_storage_changes.push(StorageChange { slot: *slot, before, after: *after });
+        }
+    }
+
+    Ok(Simulation {
+        block: block.number,
+        nonce,
+        outcome,
+        gas_used,
+        logs,
+        balance_changes,
+        safe_before,
+        safe_after,
+        other_storage_changes,
+    })
+}
+
+/// Simulates a Safe transaction on the latest block of a JSON-RPC node, which must be on
+/// `chain_id`.
+pub fn simulate_on_rpc(
+    rpc_url: &str,
+    chain_id: ChainId,
+    tx: &TxInput,
+    safe_address: Address,
+    nonce: u64,
+) -> Result<Simulation, String> {
+    let db = RpcDb::latest(rpc_url)?;
+    if db.block.chain_id != chain_id {
+        return Err(format!(
+            "{} is a node of chain {}, not {}",
+            rpc_url, db.block.chain_id, chain_id
+        ));
+    }
+    let block = db.block.clone();
+    simulate(db, &block, tx, safe_address, nonce)
+}
+
+/// Warns about a simulation that does not execute.
+pub fn check_simulation(simulation: &Simulation) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    match &simulation.outcome {
+        SimulationOutcome::Success => {}
+        SimulationOutcome::ExecutionFailure => {
+            warnings.simulation_issues.push(SimulationIssue::ExecutionFailure)
+        }
+        SimulationOutcome::Reverted(reason) | SimulationOutcome::Halted(reason) => {
+            warnings.simulation_issues.push(SimulationIssue::Reverted(reason.clone()))
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{hex, primitives::address};
+    use revm::db::EmptyDBTyped;
+
+    const SAFE: Address = address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1");
+    const OWNER: Address = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+    const TOKEN: Address = address!("0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238");
+
+    fn block() -> SimulationBlock {
+        SimulationBlock { chain_id: 11155111, number: 1, timestamp: 1, gas_limit: 30_000_000 }
+    }
+
+    /// A single owner Safe with a threshold of two, running `code` instead of a singleton.
+    fn safe_with_code(code: &str) -> CacheDB<EmptyDBTyped<String>> {
+        let mut db = CacheDB::new(EmptyDBTyped::<String>::new());
+        let code = Bytecode::new_raw(hex::decode(code).unwrap().into());
+        db.insert_account_info(
+            to_evm_address(SAFE),
+            AccountInfo::new(U256::ZERO, 1, code.hash_slow(), code),
+        );
+        let storage = [
+            (mapping_slot(SENTINEL_OWNERS, OWNERS_SLOT), OWNER.into_word().into()),
+            (mapping_slot(OWNER, OWNERS_SLOT), SENTINEL_OWNERS.into_word().into()),
+            (OWNER_COUNT_SLOT, U256::from(1)),
+            (THRESHOLD_SLOT, U256::from(2)),
+        ];
+        for (slot, value) in storage {
+            db.insert_account_storage(to_evm_address(SAFE), slot, value).unwrap();
+        }
+        db
+    }
+
+    fn tx() -> TxInput {
+        TxInput::new(
+            OWNER,
+            U256::ZERO,
+            "0x".to_string(),
+            0,
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            Address::ZERO,
+            Address::ZERO,
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_simulate() {
+        // sstore(0, caller()) sstore(0x99, 42) mstore(0, 1) return(0, 32)
+        let db = safe_with_code("33600055602a609955600160005260206000f3");
+        let simulation = simulate(db, &block(), &tx(), SAFE, 4).unwrap();
+
+        assert_eq!(simulation.outcome, SimulationOutcome::Success);
+        // The extra owner and threshold of the simulation do not show
+        assert_eq!(simulation.safe_before.owners, [OWNER]);
+        assert_eq!(simulation.safe_after.owners, [OWNER]);
+        assert_eq!(simulation.safe_after.threshold, U256::from(2));
+        assert_eq!(simulation.safe_after.singleton, SIMULATION_OWNER);
+        assert_eq!(simulation.other_storage_changes, [StorageChange {
+            slot: U256::from(0x99),
+            before: U256::ZERO,
+            after: U256::from(42),
+        }]);
+        assert!(check_simulation(&simulation).simulation_issues.is_empty());
+        // The stub does not increase the nonce
+        assert_eq!(simulation.expected_nonce(), U256::from(5));
+        assert_eq!(simulation.safe_after.nonce, U256::ZERO);
+
+        // revert(0, 0)
+        let reverted = simulate(safe_with_code("60006000fd"), &block(), &tx(), SAFE, 4).unwrap();
+        assert_eq!(reverted.outcome, SimulationOutcome::Reverted("no reason".to_string()));
+        assert_eq!(reverted.safe_after, reverted.safe_before);
+        assert_eq!(reverted.expected_nonce(), reverted.safe_before.nonce);
+
+        assert!(simulate(safe_with_code(""), &block(), &tx(), SAFE, 4).is_err());
+    }
+
+    #[test]
+    fn test_balance_changes() {
+        let transfer = |from: Address, to: Address, amount: u64| SimulatedLog {
+            address: TOKEN,
+            topics: vec![
+                keccak256("Transfer(address,address,uint256)"),
+                from.into_word(),
+                to.into_word(),
+            ],
+            data: U256::from(amount).to_be_bytes_vec().into(),
+        };
+        let logs = [transfer(OWNER, SAFE, 5), transfer(SAFE, OWNER, 7)];
+        assert_eq!(logs[0].event(), Some("Transfer(address,address,uint256)"));
+
+        let eth = vec![(OWNER, U256::from(10), U256::from(10)), (SAFE, U256::from(3), U256::ZERO)];
+        assert_eq!(balance_changes(eth, &logs, SAFE), [
+            BalanceChange { holder: SAFE, token: None, delta: I256::try_from(-3).unwrap() },
+            BalanceChange { holder: SAFE, token: Some(TOKEN), delta: I256::try_from(-2).unwrap() },
+            BalanceChange { holder: OWNER, token: Some(TOKEN), delta: I256::try_from(2).unwrap() },
+        ]);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::output::{SafeWarnings, SiweIssue};
+use alloy::primitives::{Address, ChainId};
+use chrono::{DateTime, FixedOffset, Utc};
+use url::Url;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// A Sign-In with Ethereum (EIP-4361) message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage {
+    /// Authority requesting the sign-in, optionally prefixed with a scheme
+    pub domain: String,
+    pub address: Address,
+    pub statement: Option<String>,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: ChainId,
+    pub nonce: String,
+    pub issued_at: DateTime<FixedOffset>,
+    pub expiration_time: Option<DateTime<FixedOffset>>,
+    pub not_before: Option<DateTime<FixedOffset>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SiweMessage {
+    /// Parses an EIP-4361 message. Returns `Ok(None)` for messages that are not sign-in requests,
+    /// and an error for sign-in requests that are malformed.
+    pub fn parse(message: &str) -> Result<Option<Self>, String> {
+        let mut lines = message.lines();
+        let Some(domain) = lines.next().and_then(|line| line.strip_suffix(HEADER_SUFFIX)) else {
+            return Ok(None);
+        };
+
+        let address = lines
+            .next()
+            .ok_or("missing address")?
+            .parse::<Address>()
+            .map_err(|e| format!("invalid address: {}", e))?;
+        if lines.next() != Some("") {
+            return Err("expected an empty line after the address".to_string());
+        }
+
+        // The statement is optional, some wallets also omit the empty line that replaces it
+        let mut rest = lines.peekable();
+        let mut statement = None;
+        if rest.peek() == Some(&"") {
+            rest.next();
+        } else if rest.peek().is_some_and(|line| !line.starts_with("URI: ")) {
+            statement = rest.next().map(str::to_string);
+            if rest.next() != Some("") {
+                return Err("expected an empty line after the statement".to_string());
+            }
+        }
+
+        let mut fields = Vec::new();
+        let mut resources = Vec::new();
+        while let Some(line) = rest.next() {
+            if line == "Resources:" {
+                for resource in rest.by_ref() {
+                    let resource = resource
+                        .strip_prefix("- ")
+                        .ok_or_else(|| format!("invalid resource line {:?}", resource))?;
+                    resources.push(resource.to_string());
+                }
+                break;
+            }
+            let (key, value) =
+                line.split_once(": ").ok_or_else(|| format!("invalid field line {:?}", line))?;
+            fields.push((key, value));
+        }
+        let field = |key: &str| fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let required = |key: &str| field(key).ok_or_else(|| format!("missing {:?}", key));
+        let timestamp = |key: &str, value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map_err(|e| format!("invalid {:?} {:?}: {}", key, value, e))
+        };
+
+        Ok(Some(Self {
+            domain: domain.to_string(),
+            address,
+            statement,
+            uri: required("URI")?.to_string(),
+            version: required("Version")?.to_string(),
+            chain_id: required("Chain ID")?
+                .parse()
+                .map_err(|e| format!("invalid \"Chain ID\": {}", e))?,
+            nonce: required("Nonce")?.to_string(),
+            issued_at: timestamp("Issued At", required("Issued At")?)?,
+            expiration_time: field("Expiration Time")
+                .map(|value| timestamp("Expiration Time", value))
+                .transpose()?,
+            not_before: field("Not Before")
+                .map(|value| timestamp("Not Before", value))
+                .transpose()?,
+            request_id: field("Request ID").map(str::to_string),
+            resources,
+        }))
+    }
+
+    /// Host of the domain, without scheme, user info or port.
+    fn domain_host(&self) -> String {
+        let authority = self.domain.split_once("://").map_or(&*self.domain, |(_, rest)| rest);
+        let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        let host = match host.strip_prefix('[') {
+            Some(ipv6) => ipv6.split_once(']').map_or(ipv6, |(ip, _)| ip),
+            None => host.split_once(':').map_or(host, |(host, _)| host),
+        };
+        host.to_ascii_lowercase()
+    }
+}
+
+/// Checks that a sign-in request is for the Safe and chain being signed for, is currently valid
+/// and comes from the site it sends the user back to.
+pub fn check_siwe_message(
+    siwe: &SiweMessage,
+    chain_id: ChainId,
+    safe_address: Address,
+    now: DateTime<Utc>,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+
+    if siwe.chain_id != chain_id {
+        warnings.siwe_issues.push(SiweIssue::ChainMismatch { message: siwe.chain_id, chain_id });
+    }
+    if siwe.address != safe_address {
+        warnings
+            .siwe_issues
+            .push(SiweIssue::AddressMismatch { message: siwe.address, safe_address });
+    }
+    if let Some(expiration_time) = siwe.expiration_time.filter(|time| *time <= now) {
+        warnings.siwe_issues.push(SiweIssue::Expired(expiration_time.to_rfc3339()));
+    }
+    if let Some(not_before) = siwe.not_before.filter(|time| *time > now) {
+        warnings.siwe_issues.push(SiweIssue::NotYetValid(not_before.to_rfc3339()));
+    }
+    let uri_host = Url::parse(&siwe.uri)
+        .ok()
+        .and_then(|uri| uri.host_str().map(|host| host.trim_matches(['[', ']']).to_string()));
+    if uri_host.as_deref() != Some(siwe.domain_host().as_str()) {
+        warnings
+            .siwe_issues
+            .push(SiweIssue::HostMismatch { domain: siwe.domain.clone(), uri: siwe.uri.clone() });
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use std::fs;
+
+    const SAFE: Address = address!("0xfA3430d84324ABC9ac8AAf30B2D26260F5172ad0");
+
+    fn message() -> SiweMessage {
+        let message = fs::read_to_string("../../test/sign_in_message.txt").unwrap();
+        SiweMessage::parse(message.trim()).unwrap().unwrap()
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse_siwe_message() {
+        let siwe = message();
+        assert_eq!(siwe.domain, "rewards.chain.link");
+        assert_eq!(siwe.address, SAFE);
+        assert_eq!(siwe.statement, None);
+        assert_eq!(siwe.uri, "https://rewards.chain.link");
+        assert_eq!(siwe.chain_id, 1);
+        assert_eq!(
+            siwe.nonce,
+            "0x587aff112e2a4ba316dcb93fcdfa4f5382d7eab66c2d9bb7ebe2b19ac4e075ea"
+        );
+        assert_eq!(
+            siwe.issued_at,
+            DateTime::parse_from_rfc3339("2025-05-08T16:37:09.140Z").unwrap()
+        );
+        assert_eq!(
+            siwe.expiration_time,
+            Some(DateTime::parse_from_rfc3339("2025-05-08T16:52:09.140Z").unwrap())
+        );
+
+        assert_eq!(SiweMessage::parse("Hello, world"), Ok(None));
+        let truncated = "example.com wants you to sign in with your Ethereum account:\n0xfA3430d84324ABC9ac8AAf30B2D26260F5172ad0\n\nURI: https://example.com";
+        assert!(SiweMessage::parse(truncated).is_err());
+    }
+
+    #[test]
+    fn test_parse_siwe_message_with_statement_and_resources() {
+        let message = "https://example.com:3000 wants you to sign in with your Ethereum account:
+0xfA3430d84324ABC9ac8AAf30B2D26260F5172ad0
+
+Sign in to the dashboard.
+
+URI: https://example.com:3000/login
+Version: 1
+Chain ID: 100
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/
+- https://example.com/my-web2-claim.json";
+        let siwe = SiweMessage::parse(message).unwrap().unwrap();
+
+        assert_eq!(siwe.statement.as_deref(), Some("Sign in to the dashboard."));
+        assert_eq!(siwe.chain_id, 100);
+        assert_eq!(siwe.resources.len(), 2);
+        assert!(
+            check_siwe_message(&siwe, 100, SAFE, at("2021-10-01T00:00:00Z")).siwe_issues.is_empty()
+        );
+    }
+
+    #[test]
+    fn test_check_siwe_message() {
+        let siwe = message();
+        assert!(
+            check_siwe_message(&siwe, 1, SAFE, at("2025-05-08T16:40:00Z")).siwe_issues.is_empty()
+        );
+
+        let warnings = check_siwe_message(&siwe, 100, Address::ZERO, at("2025-05-09T00:00:00Z"));
+        assert_eq!(warnings.siwe_issues, vec![
+            SiweIssue::ChainMismatch { message: 1, chain_id: 100 },
+            SiweIssue::AddressMismatch { message: SAFE, safe_address: Address::ZERO },
+            SiweIssue::Expired("2025-05-08T16:52:09.140+00:00".to_string()),
+        ]);
+
+        let phishing =
+            SiweMessage { uri: "https://rewards.chain.link.evil.xyz".to_string(), ..siwe };
+        assert_eq!(
+            check_siwe_message(&phishing, 1, SAFE, at("2025-05-08T16:40:00Z")).siwe_issues,
+            vec![SiweIssue::HostMismatch {
+                domain: "rewards.chain.link".to_string(),
+                uri: "https://rewards.chain.link.evil.xyz".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use alloy::{
+    hex,
+    primitives::{Address, ChainId, U256},
+    sol,
+    sol_types::SolCall,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+const DEFAULT_TOKEN_LIST: &str = include_str!("../assets/tokenlist.json");
+
+sol! {
+    function transfer(address to, uint256 amount);
+    function transferFrom(address from, address to, uint256 amount);
+    function approve(address spender, uint256 amount);
+    function increaseAllowance(address spender, uint256 addedValue);
+    function setApprovalForAll(address operator, bool approved);
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenInfo {
+    pub chain_id: ChainId,
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// A file in the token list format (`{"tokens": [...]}`).
+#[derive(Debug, Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenInfo>,
+}
+
+/// Well-known tokens of a single chain, bundled ones first and user-supplied lists on top.
+#[derive(Debug, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<Address, TokenInfo>,
+}
+
+impl TokenRegistry {
+    pub fn load(
+        chain_id: ChainId,
+        user_lists: &[impl AsRef<Path>],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut registry = Self::default();
+        registry.extend(chain_id, serde_json::from_str(DEFAULT_TOKEN_LIST)?);
+        for path in user_lists {
+            let content = fs::read_to_string(path.as_ref()).map_err(|e| {
+                format!("Failed to read token list {}: {}", path.as_ref().display(), e)
+            })?;
+            let list = serde_json::from_str(&content).map_err(|e| {
+                format!("Failed to parse token list {}: {}", path.as_ref().display(), e)
+            })?;
+            registry.extend(chain_id, list);
+        }
+        Ok(registry)
+    }
+
+    fn extend(&mut self, chain_id: ChainId, list: TokenList) {
+        for token in list.tokens.into_iter().filter(|token| token.chain_id == chain_id) {
+            self.tokens.insert(token.address, token);
+        }
+    }
+
+    pub fn get(&self, address: Address) -> Option<&TokenInfo> {
+        self.tokens.get(&address)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenCall {
+    Transfer {
+        to: Address,
+        amount: U256,
+    },
+    TransferFrom {
+        from: Address,
+        to: Address,
+        amount: U256,
+    },
+    Approve {
+        spender: Address,
+        amount: U256,
+    },
+    IncreaseAllowance {
+        spender: Address,
+        amount: U256,
+    },
+    /// ERC-721 / ERC-1155 operator approval over a whole collection.
+    SetApprovalForAll {
+        operator: Address,
+        approved: bool,
+    },
+}
+
+impl TokenCall {
+    /// Decodes ERC-20 `transfer`, `transferFrom`, `approve` and `increaseAllowance` calldata, as
+    /// well as ERC-721 / ERC-1155 `setApprovalForAll`.
+    pub fn decode(data: &str) -> Option<Self> {
+        let bytes = hex::decode(data).ok()?;
+        let selector: [u8; 4] = bytes.get(..4)?.try_into().ok()?;
+
+        match selector {
+            transferCall::SELECTOR => {
+                let call = transferCall::abi_decode(&bytes, true).ok()?;
+                Some(Self::Transfer { to: call.to, amount: call.amount })
+            }
+            transferFromCall::SELECTOR => {
+                let call = transferFromCall::abi_decode(&bytes, true).ok()?;
+                Some(Self::TransferFrom { from: call.from, to: call.to, amount: call.amount })
+            }
+            approveCall::SELECTOR => {
+                let call = approveCall::abi_decode(&bytes, true).ok()?;
+                Some(Self::Approve { spender: call.spender, amount: call.amount })
+            }
+            increaseAllowanceCall::SELECTOR => {
+                let call = increaseAllowanceCall::abi_decode(&bytes, true).ok()?;
+                Some(Self::IncreaseAllowance { spender: call.spender, amount: call.addedValue })
+            }
+            setApprovalForAllCall::SELECTOR => {
+                let call = setApprovalForAllCall::abi_decode(&bytes, true).ok()?;
+                Some(Self::SetApprovalForAll { operator: call.operator, approved: call.approved })
+            }
+            _ => None,
+        }
+    }
+
+    /// Address receiving tokens or an allowance from the Safe.
+    pub fn counterparty(&self) -> Address {
+        match self {
+            Self::Transfer { to, .. } | Self::TransferFrom { to, .. } => *to,
+            Self::Approve { spender, .. } | Self::IncreaseAllowance { spender, .. } => *spender,
+            Self::SetApprovalForAll { operator, .. } => *operator,
+        }
+    }
+
+    pub fn amount(&self) -> Option<U256> {
+        match self {
+            Self::Transfer { amount, .. }
+            | Self::TransferFrom { amount, .. }
+            | Self::Approve { amount, .. }
+            | Self::IncreaseAllowance { amount, .. } => Some(*amount),
+            Self::SetApprovalForAll { .. } => None,
+        }
+    }
+
+    /// Human-readable summary, e.g. `transfer 25,000.00 USDC to 0x92D0...`.
+    ///
+    /// Amounts of tokens that are not on any list are shown in raw units. Addresses are rendered
+    /// with `address`, which lets callers attach labels.
+    pub fn describe(
+        &self,
+        token: Option<&TokenInfo>,
+        address: impl Fn(Address) -> String,
+    ) -> String {
+        let is_allowance = matches!(self, Self::Approve { .. } | Self::IncreaseAllowance { .. });
+        let amount = match (self.amount(), token) {
+            (Some(amount), token) if is_allowance && is_unlimited(amount) => {
+                format!("unlimited {}", token.map_or("(unknown token)", |t| t.symbol.as_str()))
+            }
+            (Some(amount), Some(token)) => {
+                format!("{} {}", format_units(amount, token.decimals), token.symbol)
+            }
+            (Some(amount), None) => format!("{} (raw units of unknown token)", amount),
+            (None, _) => String::new(),
+        };
+        match self {
+            Self::Transfer { to, .. } => format!("transfer {} to {}", amount, address(*to)),
+            Self::TransferFrom { from, to, .. } => {
+                format!("transferFrom {} from {} to {}", amount, address(*from), address(*to))
+            }
+            Self::Approve { spender, .. } => {
+                format!("approve {} for {}", amount, address(*spender))
+            }
+            Self::IncreaseAllowance { spender, .. } => {
+                format!("increaseAllowance by {} for {}", amount, address(*spender))
+            }
+            Self::SetApprovalForAll { operator, approved } => {
+                format!("setApprovalForAll({}) for operator {}", approved, address(*operator))
+            }
+        }
+    }
+}
+
+/// Formats a raw token amount with thousands separators, keeping at least two decimals and
+/// never dropping significant digits.
+pub fn format_units(amount: U256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = decimals as usize;
+    let (integer, fraction) = if digits.len() > decimals {
+        digits.split_at(digits.len() - decimals)
+    } else {
+        ("0", digits.as_str())
+    };
+    let fraction = format!("{:0>width$}", fraction, width = decimals);
+    let fraction = fraction.trim_end_matches('0');
+    let fraction = format!("{:0<2}", fraction);
+
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+
+    format!("{}.{}", grouped, fraction)
+}
+
+/// Parses a human-readable amount such as `10000` or `0.5` into raw units.
+pub fn parse_units(amount: &str, decimals: u8) -> Option<U256> {
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || fraction.len() > decimals as usize
+        || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{:0<width$}", integer, fraction, width = decimals as usize);
+    U256::from_str_radix(if digits.is_empty() { "0" } else { &digits }, 10).ok()
+}
+
+/// Whether an approval amount is `type(uint256).max` or another value tokens commonly treat as
+/// infinite (`type(uint96).max`, anything from `type(uint128).max` up).
+pub fn is_unlimited(amount: U256) -> bool {
+    amount >= U256::from(u128::MAX) || amount == (U256::from(1) << 96) - U256::from(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(U256::from(25_000_000_000u64), 6), "25,000.00");
+        assert_eq!(format_units(U256::from(1_500_000_000_000_000_000u128), 18), "1.50");
+        assert_eq!(format_units(U256::from(123u64), 6), "0.000123");
+        assert_eq!(format_units(U256::from(999u64), 0), "999.00");
+        assert_eq!(format_units(U256::from(1_234_567_891u64), 3), "1,234,567.891");
+        assert_eq!(format_units(U256::ZERO, 18), "0.00");
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_units("10000", 6), Some(U256::from(10_000_000_000u64)));
+        assert_eq!(parse_units("0.5", 18), Some(U256::from(500_000_000_000_000_000u64)));
+        assert_eq!(parse_units(".5", 1), Some(U256::from(5)));
+        assert_eq!(parse_units("1.0000001", 6), None);
+        assert_eq!(parse_units("1e6", 6), None);
+        assert_eq!(parse_units("", 6), None);
+    }
+
+    #[test]
+    fn test_is_unlimited() {
+        assert!(is_unlimited(U256::MAX));
+        assert!(is_unlimited((U256::from(1) << 96) - U256::from(1)));
+        assert!(!is_unlimited(U256::from(25_000_000_000u64)));
+    }
+
+    #[test]
+    fn test_decode_and_describe_transfer() {
+        let data = "0xa9059cbb00000000000000000000000092d0ebaf7eb707f0650f9471e61348f4656c29bc00000000000000000000000000000000000000000000000000000005d21dba00";
+        let call = TokenCall::decode(data).unwrap();
+        assert_eq!(call, TokenCall::Transfer {
+            to: address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC"),
+            amount: U256::from(25_000_000_000u64)
+        });
+
+        let registry = TokenRegistry::load(1, &[] as &[&str]).unwrap();
+        assert_eq!(
+            call.describe(registry.get(USDC), |a| format!("{a} (Treasury)")),
+            "transfer 25,000.00 USDC to 0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC (Treasury)"
+        );
+        assert!(
+            call.describe(None, |a| a.to_string())
+                .contains("25000000000 (raw units of unknown token)")
+        );
+    }
+
+    #[test]
+    fn test_decode_approve() {
+        let data = "0x095ea7b3000000000000000000000000fe2f653f6579de62aaf8b186e618887d03fa31260000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            TokenCall::decode(data),
+            Some(TokenCall::Approve {
+                spender: address!("0xfe2f653f6579de62aaf8b186e618887d03fa3126"),
+                amount: U256::from(1)
+            })
+        );
+        let data = "0xa22cb465000000000000000000000000fe2f653f6579de62aaf8b186e618887d03fa31260000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            TokenCall::decode(data),
+            Some(TokenCall::SetApprovalForAll {
+                operator: address!("0xfe2f653f6579de62aaf8b186e618887d03fa3126"),
+                approved: true
+            })
+        );
+        let unlimited = TokenCall::Approve { spender: Address::ZERO, amount: U256::MAX };
+        let registry = TokenRegistry::load(1, &[] as &[&str]).unwrap();
+        assert_eq!(
+            unlimited.describe(registry.get(USDC), |a| a.to_string()),
+            "approve unlimited USDC for 0x0000000000000000000000000000000000000000"
+        );
+        assert_eq!(TokenCall::decode("0x"), None);
+        assert_eq!(TokenCall::decode("0x12345678"), None);
+    }
+
+    #[test]
+    fn test_registry_is_per_chain_and_user_lists_override() {
+        let registry = TokenRegistry::load(42161, &[] as &[&str]).unwrap();
+        assert!(registry.get(USDC).is_none());
+
+        let path =
+            std::env::temp_dir().join(format!("safe-hash-tokens-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{"tokens":[{"chainId":1,"address":"0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48","symbol":"MYUSDC","decimals":6}]}"#,
+        )
+        .unwrap();
+        let registry = TokenRegistry::load(1, &[&path]).unwrap();
+        assert_eq!(registry.get(USDC).unwrap().symbol, "MYUSDC");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bundled_addresses_are_checksummed() {
+        let value: serde_json::Value = serde_json::from_str(DEFAULT_TOKEN_LIST).unwrap();
+        for token in value["tokens"].as_array().unwrap() {
+            let raw = token["address"].as_str().unwrap();
+            let address: Address = raw.parse().unwrap();
+            assert_eq!(address.to_checksum(None), raw);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use alloy::primitives::{Address, ChainId, U256};
 use safe_utils::{CallDataHasher, DomainHasher, SafeHasher, SafeWalletVersion, TxMessageHasher};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TxInput {
     base_gas: Option<U256>,
@@ -20,6 +20,7 @@ pub struct TxInput {
 }
 
 impl TxInput {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         to: Address,
         value: U256,
@@ -55,6 +56,22 @@ impl TxInput {
     }
 }
 
+/// What owners sign: the transaction itself or, with nested Safes, the approval of the outermost
+/// nested Safe.
+pub struct SigningTarget {
+    pub safe_address: Address,
+    /// Safe nonce of the transaction, unused for a `SafeMessage` approval
+    pub nonce: u64,
+    /// The transaction, `None` for a `SafeMessage` approval
+    pub tx_data: Option<TxInput>,
+    pub hashes: SafeHashes,
+    /// Signatures of the owners who already confirmed, from the transaction service
+    pub confirmations: Vec<String>,
+    pub threshold: Option<u64>,
+    /// Whether a warning that should stop owners from signing was shown
+    pub blocking_warnings: bool,
+}
+
 pub fn tx_signing_hashes(
     tx_data: &TxInput,
     safe_address: Address,
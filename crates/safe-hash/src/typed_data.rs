@@ -0,0 +1,796 @@
+use crate::{
+    output::{DomainIssue, SafeWarnings, TypedDataIssue},
+    tokens::{TokenRegistry, format_units, is_unlimited},
+    tx_signing::TxInput,
+};
+use alloy::{
+    dyn_abi::TypedData,
+    hex,
+    primitives::{Address, Bytes, ChainId, U256, address},
+};
+use chrono::DateTime;
+use safe_utils::SafeWalletVersion;
+use serde_json::Value;
+
+/// Permit2, deployed at the same address on every chain.
+pub const PERMIT2: Address = address!("0x000000000022D473030F116dDEE9F6B43aC78BA3");
+/// Seaport 1.5 and 1.6.
+pub const SEAPORT: [Address; 2] = [
+    address!("0x00000000000000ADc04C56Bf30aC9d3c0aAF14dC"),
+    address!("0x0000000000000068F116a894984e2DB1123eB395"),
+];
+/// CoW Protocol's GPv2Settlement, which verifies CoW orders.
+pub const COW_SETTLEMENT: Address = address!("0x9008D19f58AAbD9eD0D60971565AA8510560ab41");
+
+/// Domain names of well-known protocols and the contracts that verify their messages.
+const KNOWN_DOMAINS: [(&str, &[Address]); 3] =
+    [("Permit2", &[PERMIT2]), ("Seaport", &SEAPORT), ("Gnosis Protocol", &[COW_SETTLEMENT])];
+
+/// Deadlines further out than this are flagged, a signature that stays valid this long is as good
+/// as a permanent one.
+const FAR_FUTURE_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+/// A token amount in a typed message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAmount {
+    pub token: Address,
+    pub amount: U256,
+}
+
+/// An item of a Seaport order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaportItem {
+    /// 0 native, 1 ERC-20, 2 ERC-721, 3 ERC-1155, 4 and 5 ERC-721 and ERC-1155 by criteria
+    pub item_type: u8,
+    pub token: Address,
+    pub identifier: U256,
+    pub amount: U256,
+}
+
+/// What a well-known typed message approves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedDataSummary {
+    /// EIP-2612 `Permit`, verified by the token itself
+    Permit { token: Address, spender: Address, value: U256, deadline: U256 },
+    /// Permit2 `PermitSingle` and `PermitBatch` allowances
+    Permit2Allowance { spender: Address, allowances: Vec<(TokenAmount, U256)>, sig_deadline: U256 },
+    /// Permit2 `PermitTransferFrom` and `PermitBatchTransferFrom`, with or without a witness
+    Permit2Transfer { spender: Address, permitted: Vec<TokenAmount>, deadline: U256 },
+    /// Seaport `OrderComponents`, with the consideration items that go to the offerer
+    SeaportOrder {
+        offerer: Address,
+        offer: Vec<SeaportItem>,
+        consideration: Vec<SeaportItem>,
+        end_time: U256,
+    },
+    /// CoW Protocol `Order`
+    CowOrder {
+        sell: TokenAmount,
+        buy: TokenAmount,
+        receiver: Address,
+        is_sell: bool,
+        valid_to: U256,
+    },
+    /// Snapshot `Vote`, verified off-chain by Snapshot
+    SnapshotVote { space: String, proposal: String, choice: String },
+}
+
+fn uint(value: &Value, key: &str) -> Option<U256> {
+    match value.get(key)? {
+        Value::String(string) => string.parse().ok(),
+        Value::Number(number) => number.as_u64().map(U256::from),
+        _ => None,
+    }
+}
+
+fn address_of(value: &Value, key: &str) -> Option<Address> {
+    value.get(key)?.as_str()?.parse().ok()
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(string) => Some(string.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// A single object or an array of objects, as Permit2 uses for single and batch permits.
+fn one_or_many(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    }
+}
+
+fn token_amount(value: &Value) -> Option<TokenAmount> {
+    Some(TokenAmount { token: address_of(value, "token")?, amount: uint(value, "amount")? })
+}
+
+fn seaport_items(value: &Value, recipient: Option<Address>) -> Option<Vec<SeaportItem>> {
+    value
+        .as_array()?
+        .iter()
+        .filter(|item| recipient.is_none() || address_of(item, "recipient") == recipient)
+        .map(|item| {
+            Some(SeaportItem {
+                item_type: uint(item, "itemType")?.try_into().ok()?,
+                token: address_of(item, "token")?,
+                identifier: uint(item, "identifierOrCriteria")?,
+                amount: uint(item, "startAmount")?,
+            })
+        })
+        .collect()
+}
+
+/// A Safe's own typed message, as wallets send it to `eth_signTypedData`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum SafeTypedData {
+    Transaction {
+        safe_address: Address,
+        nonce: u64,
+        tx: TxInput,
+    },
+    /// A `SafeMessage`, whose bytes are usually the hash of the original message
+    Message {
+        safe_address: Address,
+        message: Bytes,
+    },
+}
+
+/// Decodes `SafeTx` and `SafeMessage` payloads verified by a Safe, `None` for any other message.
+pub fn decode_safe_typed_data(typed_data: &TypedData) -> Option<SafeTypedData> {
+    let safe_address = typed_data.domain.verifying_contract?;
+    let message = &typed_data.message;
+
+    let safe_typed_data = match typed_data.primary_type.as_str() {
+        "SafeTx" => SafeTypedData::Transaction {
+            safe_address,
+            nonce: uint(message, "nonce")?.try_into().ok()?,
+            tx: TxInput::new(
+                address_of(message, "to")?,
+                uint(message, "value")?,
+                text(message, "data").filter(|data| hex::decode(data).is_ok())?,
+                uint(message, "operation")?.try_into().ok()?,
+                uint(message, "safeTxGas")?,
+                // Called `dataGas` before Safe 1.0.0
+                uint(message, "baseGas").or_else(|| uint(message, "dataGas"))?,
+                uint(message, "gasPrice")?,
+                address_of(message, "gasToken")?,
+                address_of(message, "refundReceiver")?,
+                String::new(),
+            ),
+        },
+        "SafeMessage" => SafeTypedData::Message {
+            safe_address,
+            message: text(message, "message")?.parse().ok()?,
+        },
+        _ => return None,
+    };
+
+    Some(safe_typed_data)
+}
+
+/// The Safe version whose hashing matches the payload: the domain has a chain id from 1.3.0 on,
+/// and `SafeTx` has `dataGas` instead of `baseGas` before 1.0.0.
+pub fn safe_typed_data_version(typed_data: &TypedData) -> SafeWalletVersion {
+    let has_data_gas = typed_data
+        .resolver
+        .encode_type("SafeTx")
+        .is_ok_and(|encoded| encoded.contains("uint256 dataGas"));
+    if typed_data.domain.chain_id.is_some() {
+        SafeWalletVersion::new(1, 3, 0)
+    } else if has_data_gas {
+        SafeWalletVersion::new(0, 1, 0)
+    } else {
+        SafeWalletVersion::new(1, 2, 0)
+    }
+}
+
+/// The typed data given to `typed`, with the account a JSON-RPC request asks to sign it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedDataInput {
+    pub json: String,
+    pub signer: Option<Address>,
+}
+
+/// Reads typed data JSON, or unwraps it from an `eth_signTypedData_v4` JSON-RPC request.
+pub fn parse_typed_data_input(input: &str) -> Result<TypedDataInput, String> {
+    let value: Value =
+        serde_json::from_str(input).map_err(|e| format!("Invalid typed data JSON: {}", e))?;
+    match value.get("method") {
+        Some(_) => typed_data_from_request(&value),
+        None => Ok(TypedDataInput { json: input.to_string(), signer: None }),
+    }
+}
+
+/// Typed data of an `eth_signTypedData_v4` request, whose params are the signer and the typed
+/// data, as an object or a JSON string.
+pub fn typed_data_from_request(request: &Value) -> Result<TypedDataInput, String> {
+    let method = &request["method"];
+    if method != "eth_signTypedData_v4" {
+        return Err(format!("Expected an eth_signTypedData_v4 request, got {}", method));
+    }
+    let [signer, typed_data] = request
+        .get("params")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .and_then(|params| <&[Value; 2]>::try_from(params).ok())
+        .ok_or("eth_signTypedData_v4 params must be the signer and the typed data")?;
+    let signer = signer
+        .as_str()
+        .and_then(|signer| signer.parse().ok())
+        .ok_or_else(|| format!("Invalid signer address {}", signer))?;
+    let json = match typed_data {
+        Value::String(json) => json.clone(),
+        object => object.to_string(),
+    };
+
+    Ok(TypedDataInput { json, signer: Some(signer) })
+}
+
+/// Recognizes Permit, Permit2, Seaport, CoW and Snapshot messages by their primary type.
+pub fn summarize_typed_data(typed_data: &TypedData) -> Option<TypedDataSummary> {
+    let message = &typed_data.message;
+    let domain_name = typed_data.domain.name.as_deref();
+
+    let summary = match typed_data.primary_type.as_str() {
+        "Permit" => TypedDataSummary::Permit {
+            token: typed_data.domain.verifying_contract?,
+            spender: address_of(message, "spender")?,
+            value: uint(message, "value")?,
+            deadline: uint(message, "deadline")?,
+        },
+        "PermitSingle" | "PermitBatch" => TypedDataSummary::Permit2Allowance {
+            spender: address_of(message, "spender")?,
+            allowances: one_or_many(message.get("details")?)
+                .into_iter()
+                .map(|details| Some((token_amount(details)?, uint(details, "expiration")?)))
+                .collect::<Option<_>>()?,
+            sig_deadline: uint(message, "sigDeadline")?,
+        },
+        "PermitTransferFrom"
+        | "PermitBatchTransferFrom"
+        | "PermitWitnessTransferFrom"
+        | "PermitBatchWitnessTransferFrom" => TypedDataSummary::Permit2Transfer {
+            spender: address_of(message, "spender")?,
+            permitted: one_or_many(message.get("permitted")?)
+                .into_iter()
+                .map(token_amount)
+                .collect::<Option<_>>()?,
+            deadline: uint(message, "deadline")?,
+        },
+        "OrderComponents" => {
+            let offerer = address_of(message, "offerer")?;
+            TypedDataSummary::SeaportOrder {
+                offerer,
+                offer: seaport_items(message.get("offer")?, None)?,
+                consideration: seaport_items(message.get("consideration")?, Some(offerer))?,
+                end_time: uint(message, "endTime")?,
+            }
+        }
+        "Order" if domain_name == Some("Gnosis Protocol") => TypedDataSummary::CowOrder {
+            sell: TokenAmount {
+                token: address_of(message, "sellToken")?,
+                amount: uint(message, "sellAmount")?,
+            },
+            buy: TokenAmount {
+                token: address_of(message, "buyToken")?,
+                amount: uint(message, "buyAmount")?,
+            },
+            receiver: address_of(message, "receiver")?,
+            is_sell: text(message, "kind")? == "sell",
+            valid_to: uint(message, "validTo")?,
+        },
+        "Vote" if domain_name == Some("snapshot") => TypedDataSummary::SnapshotVote {
+            space: text(message, "space")?,
+            proposal: text(message, "proposal")?,
+            choice: text(message, "choice")?,
+        },
+        _ => return None,
+    };
+
+    Some(summary)
+}
+
+/// Formats a Unix timestamp as a UTC date, e.g. `2025-05-08 16:52:09 UTC`.
+pub fn format_timestamp(timestamp: U256) -> String {
+    u64::try_from(timestamp)
+        .ok()
+        .and_then(|seconds| i64::try_from(seconds).ok())
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map_or_else(
+            || format!("timestamp {}", timestamp),
+            |date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        )
+}
+
+fn format_amount(amount: &TokenAmount, tokens: &TokenRegistry) -> String {
+    match tokens.get(amount.token) {
+        Some(token) if is_unlimited(amount.amount) => format!("unlimited {}", token.symbol),
+        None if is_unlimited(amount.amount) => format!("unlimited {}", amount.token),
+        Some(token) => format!("{} {}", format_units(amount.amount, token.decimals), token.symbol),
+        None => format!("{} (raw units of {})", amount.amount, amount.token),
+    }
+}
+
+fn format_seaport_item(item: &SeaportItem, tokens: &TokenRegistry) -> String {
+    match item.item_type {
+        0 => format!("{} ETH", format_units(item.amount, 18)),
+        1 => format_amount(&TokenAmount { token: item.token, amount: item.amount }, tokens),
+        2 => format!("NFT #{} of {}", item.identifier, item.token),
+        3 => format!("{} x #{} of {}", item.amount, item.identifier, item.token),
+        _ => format!("any NFT of {} matching the order criteria", item.token),
+    }
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    let items = items.collect::<Vec<_>>();
+    if items.is_empty() { "nothing".to_string() } else { items.join(", ") }
+}
+
+impl TypedDataSummary {
+    /// Human-readable summary, e.g. `Permit2 allowance: 1,000.00 USDC to spender 0x... until
+    /// 2025-06-01 00:00:00 UTC`.
+    pub fn describe(&self, tokens: &TokenRegistry) -> String {
+        match self {
+            Self::Permit { token, spender, value, deadline } => format!(
+                "Permit: {} to spender {} until {}",
+                format_amount(&TokenAmount { token: *token, amount: *value }, tokens),
+                spender,
+                format_timestamp(*deadline)
+            ),
+            Self::Permit2Allowance { spender, allowances, .. } => format!(
+                "Permit2 allowance: {} to spender {}",
+                join(allowances.iter().map(|(amount, expiration)| format!(
+                    "{} until {}",
+                    format_amount(amount, tokens),
+                    format_timestamp(*expiration)
+                ))),
+                spender
+            ),
+            Self::Permit2Transfer { spender, permitted, deadline } => format!(
+                "Permit2 transfer: up to {} by spender {} until {}",
+                join(permitted.iter().map(|amount| format_amount(amount, tokens))),
+                spender,
+                format_timestamp(*deadline)
+            ),
+            Self::SeaportOrder { offer, consideration, end_time, .. } => format!(
+                "Seaport order offering {} for {} until {}",
+                join(offer.iter().map(|item| format_seaport_item(item, tokens))),
+                join(consideration.iter().map(|item| format_seaport_item(item, tokens))),
+                format_timestamp(*end_time)
+            ),
+            Self::CowOrder { sell, buy, receiver, is_sell, valid_to } => {
+                let (verb, limit) =
+                    if *is_sell { ("selling", "for at least") } else { ("buying", "for at most") };
+                let (first, second) = if *is_sell { (sell, buy) } else { (buy, sell) };
+                let receiver = if *receiver == Address::ZERO {
+                    String::new()
+                } else {
+                    format!(", paid to {}", receiver)
+                };
+                format!(
+                    "CoW order {} {} {} {}{} until {}",
+                    verb,
+                    format_amount(first, tokens),
+                    limit,
+                    format_amount(second, tokens),
+                    receiver,
+                    format_timestamp(*valid_to)
+                )
+            }
+            Self::SnapshotVote { space, proposal, choice } => {
+                format!("Snapshot vote in {} on proposal {}: choice {}", space, proposal, choice)
+            }
+        }
+    }
+}
+
+/// Flags unlimited amounts, deadlines more than a year after `now` and well-known message types
+/// whose verifying contract is not the protocol's.
+pub fn check_typed_data(
+    summary: &TypedDataSummary,
+    typed_data: &TypedData,
+    tokens: &TokenRegistry,
+    now: u64,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    let verifying_contract = typed_data.domain.verifying_contract;
+    let unknown_contract = || TypedDataIssue::UnknownVerifyingContract {
+        primary_type: typed_data.primary_type.clone(),
+        contract: verifying_contract,
+    };
+
+    let (amounts, deadlines): (Vec<TokenAmount>, Vec<U256>) = match summary {
+        TypedDataSummary::Permit { token, value, deadline, .. } => {
+            if tokens.get(*token).is_none() {
+                warnings.typed_data_issues.push(unknown_contract());
+            }
+            (vec![TokenAmount { token: *token, amount: *value }], vec![*deadline])
+        }
+        TypedDataSummary::Permit2Allowance { allowances, sig_deadline, .. } => {
+            if verifying_contract != Some(PERMIT2) {
+                warnings.typed_data_issues.push(unknown_contract());
+            }
+            let mut deadlines =
+                allowances.iter().map(|(_, expiration)| *expiration).collect::<Vec<_>>();
+            deadlines.push(*sig_deadline);
+            (allowances.iter().map(|(amount, _)| amount.clone()).collect(), deadlines)
+        }
+        TypedDataSummary::Permit2Transfer { permitted, deadline, .. } => {
+            if verifying_contract != Some(PERMIT2) {
+                warnings.typed_data_issues.push(unknown_contract());
+            }
+            (permitted.clone(), vec![*deadline])
+        }
+        TypedDataSummary::SeaportOrder { end_time, .. } => {
+            if !verifying_contract.is_some_and(|contract| SEAPORT.contains(&contract)) {
+                warnings.typed_data_issues.push(unknown_contract());
+            }
+            (vec![], vec![*end_time])
+        }
+        TypedDataSummary::CowOrder { sell, valid_to, .. } => {
+            if verifying_contract != Some(COW_SETTLEMENT) {
+                warnings.typed_data_issues.push(unknown_contract());
+            }
+            (vec![sell.clone()], vec![*valid_to])
+        }
+        TypedDataSummary::SnapshotVote { .. } => (vec![], vec![]),
+    };
+
+    for amount in amounts.into_iter().filter(|amount| is_unlimited(amount.amount)) {
+        warnings.typed_data_issues.push(TypedDataIssue::UnlimitedAmount { token: amount.token });
+    }
+    let far_future = U256::from(now.saturating_add(FAR_FUTURE_SECONDS));
+    for deadline in deadlines.into_iter().filter(|deadline| *deadline > far_future) {
+        warnings
+            .typed_data_issues
+            .push(TypedDataIssue::FarFutureDeadline(format_timestamp(deadline)));
+    }
+
+    warnings
+}
+
+/// Checks the domain of a typed message against the chain, contract and Safe it is expected to be
+/// signed for. Any of these left out is not checked.
+pub fn check_domain(
+    typed_data: &TypedData,
+    chain_id: Option<ChainId>,
+    expected_contract: Option<Address>,
+    safe_address: Option<Address>,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    let domain = &typed_data.domain;
+
+    match (domain.chain_id, chain_id) {
+        (None, _) => warnings.domain_issues.push(DomainIssue::MissingChainId),
+        (Some(domain), Some(chain_id)) if domain != U256::from(chain_id) => {
+            warnings.domain_issues.push(DomainIssue::ChainMismatch { domain, chain_id })
+        }
+        _ => {}
+    }
+    if let Some(expected) =
+        expected_contract.filter(|expected| domain.verifying_contract != Some(*expected))
+    {
+        warnings
+            .domain_issues
+            .push(DomainIssue::ContractMismatch { domain: domain.verifying_contract, expected });
+    }
+    if let Some(safe_address) = safe_address {
+        let primary_type = typed_data.primary_type.as_str();
+        if matches!(primary_type, "SafeTx" | "SafeMessage")
+            && domain.verifying_contract != Some(safe_address)
+        {
+            warnings.domain_issues.push(DomainIssue::NotTheSafe {
+                primary_type: primary_type.to_string(),
+                domain: domain.verifying_contract,
+                safe_address,
+            });
+        }
+    }
+    if let Some(name) = domain.name.as_deref() {
+        let impersonated = KNOWN_DOMAINS.iter().find(|(known, contracts)| {
+            name.trim().eq_ignore_ascii_case(known)
+                && !domain.verifying_contract.is_some_and(|contract| contracts.contains(&contract))
+        });
+        if impersonated.is_some() {
+            warnings.domain_issues.push(DomainIssue::ImpersonatedName {
+                name: name.to_string(),
+                contract: domain.verifying_contract,
+            });
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const SPENDER: Address = address!("0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD");
+    // 2025-06-01 00:00:00 UTC
+    const NOW: u64 = 1_748_736_000;
+
+    fn tokens() -> TokenRegistry {
+        TokenRegistry::load(1, &[] as &[&str]).unwrap()
+    }
+
+    fn permit2_single(amount: &str, expiration: u64, verifying_contract: Address) -> TypedData {
+        serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "PermitDetails": [
+                    {"name": "token", "type": "address"},
+                    {"name": "amount", "type": "uint160"},
+                    {"name": "expiration", "type": "uint48"},
+                    {"name": "nonce", "type": "uint48"}
+                ],
+                "PermitSingle": [
+                    {"name": "details", "type": "PermitDetails"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "sigDeadline", "type": "uint256"}
+                ]
+            },
+            "primaryType": "PermitSingle",
+            "domain": {"name": "Permit2", "chainId": 1, "verifyingContract": verifying_contract},
+            "message": {
+                "details": {"token": USDC, "amount": amount, "expiration": expiration, "nonce": 0},
+                "spender": SPENDER,
+                "sigDeadline": (NOW + 1800).to_string()
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_permit2_allowance() {
+        let typed_data = permit2_single("1000000000", NOW + 30 * 24 * 3600, PERMIT2);
+        let summary = summarize_typed_data(&typed_data).unwrap();
+
+        assert_eq!(
+            summary.describe(&tokens()),
+            format!(
+                "Permit2 allowance: 1,000.00 USDC until 2025-07-01 00:00:00 UTC to spender {}",
+                SPENDER
+            )
+        );
+        assert!(
+            check_typed_data(&summary, &typed_data, &tokens(), NOW).typed_data_issues.is_empty()
+        );
+    }
+
+    #[test]
+    fn test_risky_permit2_allowance() {
+        let unlimited = U256::MAX.to_string();
+        let typed_data = permit2_single(&unlimited, NOW + 5 * 365 * 24 * 3600, SPENDER);
+        let summary = summarize_typed_data(&typed_data).unwrap();
+
+        assert_eq!(
+            check_typed_data(&summary, &typed_data, &tokens(), NOW).typed_data_issues,
+            vec![
+                TypedDataIssue::UnknownVerifyingContract {
+                    primary_type: "PermitSingle".to_string(),
+                    contract: Some(SPENDER),
+                },
+                TypedDataIssue::UnlimitedAmount { token: USDC },
+                TypedDataIssue::FarFutureDeadline("2030-05-31 00:00:00 UTC".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seaport_order() {
+        let collection = address!("0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D");
+        let typed_data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "OrderComponents": [
+                    {"name": "offerer", "type": "address"},
+                    {"name": "offer", "type": "OfferItem[]"},
+                    {"name": "consideration", "type": "ConsiderationItem[]"},
+                    {"name": "endTime", "type": "uint256"}
+                ],
+                "OfferItem": [
+                    {"name": "itemType", "type": "uint8"},
+                    {"name": "token", "type": "address"},
+                    {"name": "identifierOrCriteria", "type": "uint256"},
+                    {"name": "startAmount", "type": "uint256"},
+                    {"name": "endAmount", "type": "uint256"}
+                ],
+                "ConsiderationItem": [
+                    {"name": "itemType", "type": "uint8"},
+                    {"name": "token", "type": "address"},
+                    {"name": "identifierOrCriteria", "type": "uint256"},
+                    {"name": "startAmount", "type": "uint256"},
+                    {"name": "endAmount", "type": "uint256"},
+                    {"name": "recipient", "type": "address"}
+                ]
+            },
+            "primaryType": "OrderComponents",
+            "domain": {"name": "Seaport", "version": "1.6", "chainId": 1, "verifyingContract": SEAPORT[1]},
+            "message": {
+                "offerer": SPENDER,
+                "offer": [{"itemType": 2, "token": collection, "identifierOrCriteria": "123", "startAmount": "1", "endAmount": "1"}],
+                "consideration": [
+                    {"itemType": 0, "token": Address::ZERO, "identifierOrCriteria": "0", "startAmount": "9750000000000000", "endAmount": "9750000000000000", "recipient": SPENDER},
+                    {"itemType": 0, "token": Address::ZERO, "identifierOrCriteria": "0", "startAmount": "250000000000000", "endAmount": "250000000000000", "recipient": USDC}
+                ],
+                "endTime": (NOW + 3600).to_string()
+            }
+        }))
+        .unwrap();
+        let summary = summarize_typed_data(&typed_data).unwrap();
+
+        assert_eq!(
+            summary.describe(&tokens()),
+            format!(
+                "Seaport order offering NFT #123 of {} for 0.00975 ETH until 2025-06-01 01:00:00 UTC",
+                collection
+            )
+        );
+        assert!(
+            check_typed_data(&summary, &typed_data, &tokens(), NOW).typed_data_issues.is_empty()
+        );
+    }
+
+    #[test]
+    fn test_check_domain() {
+        let typed_data = permit2_single("1000000000", NOW, PERMIT2);
+        assert!(check_domain(&typed_data, Some(1), Some(PERMIT2), None).domain_issues.is_empty());
+
+        assert_eq!(check_domain(&typed_data, Some(100), Some(SPENDER), None).domain_issues, vec![
+            DomainIssue::ChainMismatch { domain: U256::from(1), chain_id: 100 },
+            DomainIssue::ContractMismatch { domain: Some(PERMIT2), expected: SPENDER },
+        ]);
+
+        let mut impersonated = permit2_single("1000000000", NOW, SPENDER);
+        impersonated.domain.chain_id = None;
+        assert_eq!(check_domain(&impersonated, Some(1), None, None).domain_issues, vec![
+            DomainIssue::MissingChainId,
+            DomainIssue::ImpersonatedName { name: "Permit2".to_string(), contract: Some(SPENDER) },
+        ]);
+    }
+
+    #[test]
+    fn test_check_safe_domain() {
+        let safe = address!("0x4087d2046A7435911fC26DCFac1c2Db26957Ab72");
+        let message = std::fs::read_to_string("../../test/eip712_message.json").unwrap();
+        let mut typed_data: TypedData = serde_json::from_str(&message).unwrap();
+        typed_data.primary_type = "SafeMessage".to_string();
+
+        assert_eq!(check_domain(&typed_data, Some(1), None, Some(safe)).domain_issues, vec![
+            DomainIssue::NotTheSafe {
+                primary_type: "SafeMessage".to_string(),
+                domain: typed_data.domain.verifying_contract,
+                safe_address: safe,
+            }
+        ]);
+        typed_data.domain.verifying_contract = Some(safe);
+        assert!(check_domain(&typed_data, Some(1), None, Some(safe)).domain_issues.is_empty());
+    }
+
+    #[test]
+    fn test_decode_safe_tx() {
+        let safe = address!("0x4087d2046A7435911fC26DCFac1c2Db26957Ab72");
+        let data = "0xa9059cbb000000000000000000000000d8da6bf26964af9d7eed9e03e53415d37aa960450000000000000000000000000000000000000000000000000de0b6b3a7640000";
+        let mut typed_data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "SafeTx": [
+                    {"name": "to", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "data", "type": "bytes"},
+                    {"name": "operation", "type": "uint8"},
+                    {"name": "safeTxGas", "type": "uint256"},
+                    {"name": "baseGas", "type": "uint256"},
+                    {"name": "gasPrice", "type": "uint256"},
+                    {"name": "gasToken", "type": "address"},
+                    {"name": "refundReceiver", "type": "address"},
+                    {"name": "nonce", "type": "uint256"}
+                ]
+            },
+            "primaryType": "SafeTx",
+            "domain": {"chainId": "42161", "verifyingContract": safe},
+            "message": {
+                "to": "0x82af49447d8a07e3bd95bd0d56f35241523fbab1",
+                "value": "0",
+                "data": data,
+                "operation": "0",
+                "safeTxGas": "0",
+                "baseGas": "0",
+                "gasPrice": "0",
+                "gasToken": Address::ZERO,
+                "refundReceiver": Address::ZERO,
+                "nonce": "29"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            decode_safe_typed_data(&typed_data),
+            Some(SafeTypedData::Transaction {
+                safe_address: safe,
+                nonce: 29,
+                tx: TxInput::new(
+                    address!("0x82af49447d8a07e3bd95bd0d56f35241523fbab1"),
+                    U256::ZERO,
+                    data.to_string(),
+                    0,
+                    U256::ZERO,
+                    U256::ZERO,
+                    U256::ZERO,
+                    Address::ZERO,
+                    Address::ZERO,
+                    String::new(),
+                ),
+            })
+        );
+        assert_eq!(safe_typed_data_version(&typed_data), SafeWalletVersion::new(1, 3, 0));
+
+        typed_data.domain.chain_id = None;
+        assert_eq!(safe_typed_data_version(&typed_data), SafeWalletVersion::new(1, 2, 0));
+    }
+
+    #[test]
+    fn test_decode_safe_message() {
+        let message = std::fs::read_to_string("../../test/another_example.json").unwrap();
+        let typed_data: TypedData = serde_json::from_str(&message).unwrap();
+
+        assert_eq!(
+            decode_safe_typed_data(&typed_data),
+            Some(SafeTypedData::Message {
+                safe_address: address!("0x35ea56fd9ead2567f339eb9564b6940b9dd5653f"),
+                message: "0x1be8e5ff60e3a11c229dd65f64fcead922ed2e56991362d76f5df5a8e7d4c1c0"
+                    .parse()
+                    .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_typed_data_input() {
+        let typed_data = std::fs::read_to_string("../../test/another_example.json").unwrap();
+        assert_eq!(
+            parse_typed_data_input(&typed_data),
+            Ok(TypedDataInput { json: typed_data.clone(), signer: None })
+        );
+
+        let value: Value = serde_json::from_str(&typed_data).unwrap();
+        let signer = "0x35ea56fd9ead2567f339eb9564b6940b9dd5653f";
+        for params in [json!([signer, typed_data]), json!([signer, value])] {
+            let request = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_signTypedData_v4", "params": params});
+            let input = parse_typed_data_input(&request.to_string()).unwrap();
+            assert_eq!(serde_json::from_str::<Value>(&input.json).unwrap(), value);
+            assert_eq!(input.signer, Some(signer.parse().unwrap()));
+        }
+
+        let personal_sign = json!({"method": "personal_sign", "params": ["0x1234", signer]});
+        assert!(parse_typed_data_input(&personal_sign.to_string()).is_err());
+        let missing = json!({"method": "eth_signTypedData_v4", "params": [signer]});
+        assert!(parse_typed_data_input(&missing.to_string()).is_err());
+        assert!(parse_typed_data_input("not json").is_err());
+    }
+
+    #[test]
+    fn test_unrecognized_typed_data() {
+        let message = std::fs::read_to_string("../../test/eip712_message.json").unwrap();
+        let typed_data: TypedData = serde_json::from_str(&message).unwrap();
+        assert_eq!(summarize_typed_data(&typed_data), None);
+    }
+}
@@ -1,8 +1,28 @@
-use crate::{etherscan::is_contract_verfied, output::SafeWarnings, tx_signing::TxInput};
-use alloy::primitives::{Address, ChainId, U256};
-use std::env::VarError;
+use crate::{
+    addressbook::AddressBook,
+    api::SafeTransaction,
+    etherscan::ContractVerifier,
+    multisend::{Call, decode_multisend},
+    output::{
+        ApprovalRisk, ConfigurationChange, LookalikeAddress, NonceIssue, RiskyApproval,
+        SafeWarnings,
+    },
+    simulate::SafeState,
+    tokens::{TokenCall, TokenRegistry, format_units, is_unlimited, parse_units},
+    tx_signing::TxInput,
+};
+use alloy::{
+    hex,
+    primitives::{Address, ChainId, U256},
+};
+use chrono::{DateTime, TimeDelta};
+use std::cmp::Ordering;
 
-pub fn check_suspicious_content(tx_data: &TxInput, chain_id: Option<ChainId>) -> SafeWarnings {
+pub fn check_suspicious_content(
+    tx_data: &TxInput,
+    chain_id: Option<ChainId>,
+    verifier: Option<&mut ContractVerifier>,
+) -> SafeWarnings {
     let mut warnings = SafeWarnings::new();
 
     // Check for delegate call
@@ -26,25 +46,548 @@ pub fn check_suspicious_content(tx_data: &TxInput, chain_id: Option<ChainId>) ->
     }
 
     // Check `to` address contract verification status
-    if !tx_data.value.is_zero() {
-        match chain_id.map(|chain_id| is_contract_verfied(&tx_data.to.to_string(), chain_id)) {
-            Some(Ok(false)) => {
-                // Note: We don't have a field for unverified contract in SafeWarnings
-                // We could add one if needed
+    if tx_data.data != "0x" && !tx_data.data.is_empty() {
+        if let (Some(chain_id), Some(verifier)) = (chain_id, verifier) {
+            match verifier.is_contract_verified(tx_data.to, chain_id) {
+                Ok(false) => warnings.unverified_contract = true,
+                Ok(true) => {}
+                Err(err) => {
+                    eprintln!(
+                        "Warning: Could not check contract verification of {}: {}",
+                        tx_data.to, err
+                    )
+                }
             }
-            Some(Err(err)) => {
-                if err.downcast_ref::<VarError>().is_some() {
-                    // Note: We don't have a field for API key warning in SafeWarnings
-                    // We could add one if needed
+        }
+    }
+
+    warnings
+}
+
+pub fn check_token_call(calls: &[Call], tokens: &TokenRegistry) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+
+    for call in calls {
+        let is_erc20_call =
+            TokenCall::decode(&call.data).is_some_and(|token_call| token_call.amount().is_some());
+        if is_erc20_call
+            && tokens.get(call.to).is_none()
+            && !warnings.unknown_tokens.contains(&call.to)
+        {
+            warnings.unknown_tokens.push(call.to);
+        }
+    }
+
+    warnings
+}
+
+/// Flags unlimited ERC-20 approvals, approvals above `threshold` (in whole tokens) or that cannot
+/// be compared with it, and ERC-721 / ERC-1155 `setApprovalForAll`.
+pub fn check_approvals(
+    calls: &[Call],
+    chain_id: ChainId,
+    tokens: &TokenRegistry,
+    address_book: &AddressBook,
+    threshold: Option<&str>,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+
+    for call in calls {
+        let (spender, risk) = match TokenCall::decode(&call.data) {
+            Some(TokenCall::Approve { spender, amount })
+            | Some(TokenCall::IncreaseAllowance { spender, amount }) => {
+                if is_unlimited(amount) {
+                    (spender, ApprovalRisk::Unlimited)
+                } else if let Some(threshold) = threshold {
+                    let unchecked = |reason: String| ApprovalRisk::ThresholdUnchecked {
+                        amount: amount.to_string(),
+                        threshold: threshold.to_string(),
+                        reason,
+                    };
+                    let risk = match tokens.get(call.to) {
+                        None => unchecked("the token's decimals are unknown".to_string()),
+                        Some(token) => match parse_units(threshold, token.decimals) {
+                            None => unchecked(format!(
+                                "it is not a {} amount, which has {} decimals",
+                                token.symbol, token.decimals
+                            )),
+                            Some(limit) if amount <= limit => continue,
+                            Some(_) => ApprovalRisk::AboveThreshold {
+                                amount: format_units(amount, token.decimals),
+                                threshold: threshold.to_string(),
+                            },
+                        },
+                    };
+                    (spender, risk)
+                } else {
+                    continue;
                 }
             }
-            None => {
-                // Note: We don't have a field for missing chain warning in SafeWarnings
-                // We could add one if needed
+            Some(TokenCall::SetApprovalForAll { operator, approved: true }) => {
+                (operator, ApprovalRisk::ApprovalForAll)
             }
-            _ => {}
+            _ => continue,
         };
+
+        warnings.risky_approvals.push(RiskyApproval {
+            token: call.to,
+            token_symbol: tokens.get(call.to).map(|token| token.symbol.clone()),
+            spender,
+            spender_label: address_book.label(chain_id, spender).map(str::to_string),
+            risk,
+        });
+    }
+
+    warnings
+}
+
+/// Flags recipients, spenders and called contracts that are not in the address book. Token
+/// contracts, MultiSend batches and the Safe itself are not counterparties.
+pub fn check_counterparties(
+    calls: &[Call],
+    chain_id: ChainId,
+    safe_address: Address,
+    address_book: &AddressBook,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+
+    for call in calls {
+        let mut counterparties = Vec::new();
+        if let Some(token_call) = TokenCall::decode(&call.data) {
+            counterparties.push(token_call.counterparty());
+        } else if decode_multisend(&call.data).is_none() {
+            counterparties.push(call.to);
+        }
+
+        for counterparty in counterparties {
+            if counterparty != safe_address
+                && address_book.label(chain_id, counterparty).is_none()
+                && !warnings.unknown_counterparties.contains(&counterparty)
+            {
+                warnings.unknown_counterparties.push(counterparty);
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Leading and trailing hex characters an attacker-generated address typically shares with the
+/// address it imitates.
+const LOOKALIKE_PREFIX_LEN: usize = 3;
+const LOOKALIKE_SUFFIX_LEN: usize = 3;
+
+fn is_lookalike(a: Address, b: Address) -> bool {
+    let (a, b) = (hex::encode(a), hex::encode(b));
+    a != b
+        && a[..LOOKALIKE_PREFIX_LEN] == b[..LOOKALIKE_PREFIX_LEN]
+        && a[a.len() - LOOKALIKE_SUFFIX_LEN..] == b[b.len() - LOOKALIKE_SUFFIX_LEN..]
+}
+
+/// Every address the transaction touches: call targets, token counterparties, gas refund
+/// settings and address-shaped arguments in the calldata.
+pub fn transaction_addresses(tx_data: &TxInput, calls: &[Call]) -> Vec<Address> {
+    let mut addresses = vec![tx_data.gas_token, tx_data.refund_receiver];
+    for call in calls {
+        addresses.push(call.to);
+        if decode_multisend(&call.data).is_some() {
+            continue;
+        }
+        if let Some(token_call) = TokenCall::decode(&call.data) {
+            addresses.push(token_call.counterparty());
+        }
+        let Ok(data) = hex::decode(&call.data) else { continue };
+        for word in data.get(4..).unwrap_or_default().chunks_exact(32) {
+            // Left-padded to 32 bytes, and not a small number
+            if word[..12].iter().all(|b| *b == 0) && word[12..16].iter().any(|b| *b != 0) {
+                addresses.push(Address::from_slice(&word[12..]));
+            }
+        }
+    }
+
+    let mut unique = Vec::new();
+    for address in addresses {
+        if address != Address::ZERO && !unique.contains(&address) {
+            unique.push(address);
+        }
+    }
+    unique
+}
+
+/// Compares every address of the transaction against known ones (address book, Safe owners,
+/// confirmation owners) and flags those sharing a prefix and suffix with a known address without
+/// being equal to it.
+pub fn check_address_poisoning(addresses: &[Address], known: &[(Address, String)]) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+
+    for address in addresses {
+        if known.iter().any(|(known, _)| known == address) {
+            continue;
+        }
+        if let Some((known, known_as)) =
+            known.iter().find(|(known, _)| is_lookalike(*address, *known))
+        {
+            warnings.address_poisoning.push(LookalikeAddress {
+                address: *address,
+                known: *known,
+                known_as: known_as.clone(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Formats a set of addresses, such as owners or modules, sorted so only membership is compared.
+pub fn format_addresses(addresses: &[Address]) -> String {
+    if addresses.is_empty() {
+        return "none".to_string();
+    }
+    let mut addresses = addresses.to_vec();
+    addresses.sort();
+    addresses.iter().map(Address::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Compares the well-known slots of the Safe's storage before and after a simulated execution. A
+/// delegatecall can rewrite them directly, without calling any owner or module management function
+/// that the method checks would catch. The nonce is expected to be `expected_nonce` afterwards.
+pub fn check_safe_configuration(
+    before: &SafeState,
+    after: &SafeState,
+    expected_nonce: U256,
+) -> SafeWarnings {
+    let slots = [
+        (
+            "Slot 0, the singleton: the contract whose code runs every call to the Safe",
+            before.singleton.to_string(),
+            after.singleton.to_string(),
+        ),
+        (
+            "Owners linked list: the accounts that can sign for the Safe",
+            format_addresses(&before.owners),
+            format_addresses(&after.owners),
+        ),
+        (
+            "Slot 3, the owner count: how many owners the threshold is checked against",
+            before.owner_count.to_string(),
+            after.owner_count.to_string(),
+        ),
+        (
+            "Slot 4, the threshold: how many owners must sign a transaction",
+            before.threshold.to_string(),
+            after.threshold.to_string(),
+        ),
+        (
+            "Slot 5, the nonce: which transaction the Safe executes next, shown as expected after this one",
+            expected_nonce.to_string(),
+            after.nonce.to_string(),
+        ),
+        (
+            "Modules linked list: contracts that execute transactions without any signature",
+            format_addresses(&before.modules),
+            format_addresses(&after.modules),
+        ),
+        (
+            "Guard slot: the contract that checks, and can block, every transaction",
+            before.guard.to_string(),
+            after.guard.to_string(),
+        ),
+        (
+            "Fallback handler slot: the contract that answers calls the Safe does not implement",
+            before.fallback_handler.to_string(),
+            after.fallback_handler.to_string(),
+        ),
+    ];
+
+    let mut warnings = SafeWarnings::new();
+    for (slot, before, after) in slots {
+        if before != after {
+            warnings.configuration_changes.push(ConfigurationChange { slot, before, after });
+        }
     }
+    warnings
+}
 
+/// Checks that `nonce` is the next one the Safe executes, given its `current_nonce` when known,
+/// and that the API transaction for it is still pending and unchanged since it was proposed.
+pub fn check_nonce(
+    nonce: u64,
+    current_nonce: Option<u64>,
+    api_tx: Option<&SafeTransaction>,
+) -> SafeWarnings {
+    let mut warnings = SafeWarnings::new();
+    if let Some(api_tx) = api_tx.filter(|api_tx| api_tx.is_executed) {
+        let transaction_hash = api_tx.transaction_hash.clone();
+        warnings.nonce_issues.push(NonceIssue::Executed { transaction_hash });
+    } else if let Some(current) = current_nonce {
+        match nonce.cmp(&current) {
+            Ordering::Less => warnings.nonce_issues.push(NonceIssue::Stale { nonce, current }),
+            Ordering::Greater => warnings.nonce_issues.push(NonceIssue::Gap { nonce, current }),
+            Ordering::Equal => {}
+        }
+    }
+
+    if let Some(api_tx) = api_tx {
+        let time = |time: &str| DateTime::parse_from_rfc3339(time).ok();
+        let submitted = time(&api_tx.submission_date);
+        let modified = time(&api_tx.modified);
+        // Creating and executing the transaction touch it too
+        let executed = api_tx.execution_date.as_deref().and_then(time);
+        if let (Some(submitted), Some(modified)) = (submitted, modified) {
+            if modified - submitted > TimeDelta::seconds(1)
+                && executed.is_none_or(|executed| modified < executed)
+            {
+                warnings.nonce_issues.push(NonceIssue::Modified {
+                    submitted: api_tx.submission_date.clone(),
+                    modified: api_tx.modified.clone(),
+                });
+            }
+        }
+    }
     warnings
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multisend::{encode_multisend, flatten_calls};
+    use alloy::{primitives::address, sol_types::SolCall};
+
+    const USDC: Address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const SPENDER: Address = address!("0xfe2f653f6579de62aaf8b186e618887d03fa3126");
+
+    fn approve(token: Address, amount: U256) -> Call {
+        let data = crate::tokens::approveCall { spender: SPENDER, amount }.abi_encode();
+        Call { operation: 0, to: token, value: U256::ZERO, data: hex::encode_prefixed(data) }
+    }
+
+    fn batch(calls: &[Call]) -> Call {
+        Call {
+            operation: 1,
+            to: address!("0x40A2aCCbd92BCA938b02010E17A5b8929b49130D"),
+            value: U256::ZERO,
+            data: encode_multisend(calls),
+        }
+    }
+
+    #[test]
+    fn test_max_approval_inside_multisend() {
+        let tokens = TokenRegistry::load(1, &[] as &[&str]).unwrap();
+        let calls = flatten_calls(batch(&[approve(USDC, U256::from(1)), approve(USDC, U256::MAX)]));
+
+        let warnings = check_approvals(&calls, 1, &tokens, &AddressBook::default(), None);
+        assert_eq!(warnings.risky_approvals.len(), 1);
+        let approval = &warnings.risky_approvals[0];
+        assert!(matches!(approval.risk, ApprovalRisk::Unlimited));
+        assert_eq!(approval.spender, SPENDER);
+        assert_eq!(approval.token_symbol.as_deref(), Some("USDC"));
+        assert!(approval.spender_label.is_none());
+    }
+
+    #[test]
+    fn test_approval_threshold() {
+        let tokens = TokenRegistry::load(1, &[] as &[&str]).unwrap();
+        let calls = [approve(USDC, U256::from(20_000_000_000u64))];
+        let book = AddressBook::default();
+
+        let warnings = check_approvals(&calls, 1, &tokens, &book, Some("10000"));
+        assert!(matches!(
+            &warnings.risky_approvals[0].risk,
+            ApprovalRisk::AboveThreshold { amount, .. } if amount == "20,000.00"
+        ));
+        assert!(
+            check_approvals(&calls, 1, &tokens, &book, Some("50000")).risky_approvals.is_empty()
+        );
+        assert!(check_approvals(&calls, 1, &tokens, &book, None).risky_approvals.is_empty());
+
+        // USDC has 6 decimals, and the threshold cannot apply to a token missing from the list
+        let warnings = check_approvals(&calls, 1, &tokens, &book, Some("0.0000001"));
+        assert!(matches!(
+            &warnings.risky_approvals[0].risk,
+            ApprovalRisk::ThresholdUnchecked { reason, .. } if reason.contains("6 decimals")
+        ));
+        let unknown = [approve(SPENDER, U256::from(20_000_000_000u64))];
+        let warnings = check_approvals(&unknown, 1, &tokens, &book, Some("10000"));
+        assert!(matches!(
+            &warnings.risky_approvals[0].risk,
+            ApprovalRisk::ThresholdUnchecked { amount, .. } if amount == "20000000000"
+        ));
+        assert!(check_approvals(&unknown, 1, &tokens, &book, None).risky_approvals.is_empty());
+    }
+
+    #[test]
+    fn test_set_approval_for_all() {
+        let tokens = TokenRegistry::load(1, &[] as &[&str]).unwrap();
+        let nft = address!("0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D");
+        let call = |approved| {
+            let data =
+                crate::tokens::setApprovalForAllCall { operator: SPENDER, approved }.abi_encode();
+            Call { operation: 0, to: nft, value: U256::ZERO, data: hex::encode_prefixed(data) }
+        };
+
+        let book = AddressBook::default();
+        let warnings = check_approvals(&[call(true)], 1, &tokens, &book, None);
+        assert!(matches!(warnings.risky_approvals[0].risk, ApprovalRisk::ApprovalForAll));
+        assert!(
+            check_approvals(&[call(false)], 1, &tokens, &book, None).risky_approvals.is_empty()
+        );
+        assert!(check_token_call(&[call(true)], &tokens).unknown_tokens.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_counterparties() {
+        let safe = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+        let friend = address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC");
+        let transfer = |to| {
+            let data = crate::tokens::transferCall { to, amount: U256::from(1) }.abi_encode();
+            Call { operation: 0, to: USDC, value: U256::ZERO, data: hex::encode_prefixed(data) }
+        };
+        let eth_to_self = Call { operation: 0, to: safe, value: U256::from(1), data: "0x".into() };
+        let calls =
+            flatten_calls(batch(&[transfer(friend), approve(USDC, U256::MAX), eth_to_self]));
+
+        let mut book = AddressBook::default();
+        book.insert(1, friend, "Friend".to_string());
+        let warnings = check_counterparties(&calls, 1, safe, &book);
+        assert_eq!(warnings.unknown_counterparties, vec![SPENDER]);
+
+        let warnings = check_counterparties(&calls, 1, safe, &AddressBook::default());
+        assert_eq!(warnings.unknown_counterparties, vec![friend, SPENDER]);
+    }
+
+    #[test]
+    fn test_address_poisoning() {
+        let owner = address!("0xc12F6A2D93C0788366FC91aeBf21b33CCCb0c0d8");
+        let poisoned = address!("0xc12A000000000000000000000000000000a0c0d8");
+        let unrelated = address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC");
+        let known = vec![(owner, "Safe owner".to_string())];
+
+        let warnings = check_address_poisoning(&[owner, poisoned, unrelated], &known);
+        assert_eq!(warnings.address_poisoning.len(), 1);
+        assert_eq!(warnings.address_poisoning[0].address, poisoned);
+        assert_eq!(warnings.address_poisoning[0].known, owner);
+        assert_eq!(warnings.address_poisoning[0].known_as, "Safe owner");
+    }
+
+    #[test]
+    fn test_transaction_addresses_include_calldata_arguments() {
+        let friend = address!("0x92D0eBAF7Eb707F0650F9471E61348f4656c29bC");
+        let data =
+            crate::tokens::transferCall { to: friend, amount: U256::from(25_000_000_000u64) }
+                .abi_encode();
+        let tx_data = TxInput::new(
+            USDC,
+            U256::ZERO,
+            hex::encode_prefixed(data),
+            0,
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            Address::ZERO,
+            Address::ZERO,
+            String::new(),
+        );
+        let calls = flatten_calls(Call {
+            operation: 0,
+            to: USDC,
+            value: U256::ZERO,
+            data: tx_data.data.clone(),
+        });
+
+        assert_eq!(transaction_addresses(&tx_data, &calls), vec![USDC, friend]);
+    }
+
+    #[test]
+    fn test_safe_configuration_changes() {
+        let owner = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+        let before = SafeState {
+            singleton: address!("0x29fcB43b46531BcA003ddC8FCB67FFE91900C762"),
+            owners: vec![owner],
+            owner_count: U256::from(1),
+            threshold: U256::from(1),
+            nonce: U256::from(7),
+            modules: Vec::new(),
+            guard: Address::ZERO,
+            fallback_handler: address!("0xfd0732Dc9E303f09fCEf3a7388Ad10A83459Ec99"),
+        };
+        let executed = SafeState { nonce: U256::from(8), ..before.clone() };
+        assert!(
+            check_safe_configuration(&before, &executed, U256::from(8))
+                .configuration_changes
+                .is_empty()
+        );
+
+        // A delegatecall that swaps the singleton and rewinds the nonce
+        let rewritten = SafeState { singleton: SPENDER, ..before.clone() };
+        let changes =
+            check_safe_configuration(&before, &rewritten, U256::from(8)).configuration_changes;
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].slot.starts_with("Slot 0, the singleton"));
+        assert_eq!(changes[0].after, SPENDER.to_string());
+        assert!(changes[1].slot.starts_with("Slot 5, the nonce"));
+        assert_eq!((changes[1].before.as_str(), changes[1].after.as_str()), ("8", "7"));
+
+        let module = SafeState { modules: vec![SPENDER], nonce: U256::from(8), ..before.clone() };
+        let changes =
+            check_safe_configuration(&before, &module, U256::from(8)).configuration_changes;
+        assert_eq!(changes[0].before, "none");
+        assert_eq!(changes[0].after, SPENDER.to_string());
+
+        // Fewer owners than the threshold lets a single owner execute
+        let owner_count = SafeState { owner_count: U256::ZERO, ..executed.clone() };
+        let changes =
+            check_safe_configuration(&before, &owner_count, U256::from(8)).configuration_changes;
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].slot.starts_with("Slot 3, the owner count"));
+        assert_eq!((changes[0].before.as_str(), changes[0].after.as_str()), ("1", "0"));
+    }
+
+    #[test]
+    fn test_nonce_issues() {
+        let json = std::fs::read_to_string("../../test/client_tx_response.json").unwrap();
+        let response: crate::api::SafeApiResponse = serde_json::from_str(&json).unwrap();
+        let executed = &response.results[0];
+
+        // Modified when it was indexed, after execution
+        assert_eq!(check_nonce(63, Some(64), Some(executed)).nonce_issues, [
+            NonceIssue::Executed { transaction_hash: executed.transaction_hash.clone() }
+        ]);
+
+        let mut pending = executed.clone();
+        pending.is_executed = false;
+        pending.execution_date = None;
+        pending.modified = pending.submission_date.clone();
+        assert!(check_nonce(63, Some(63), Some(&pending)).nonce_issues.is_empty());
+        assert_eq!(check_nonce(63, Some(64), Some(&pending)).nonce_issues, [NonceIssue::Stale {
+            nonce: 63,
+            current: 64
+        }]);
+        assert_eq!(check_nonce(63, Some(60), None).nonce_issues, [NonceIssue::Gap {
+            nonce: 63,
+            current: 60
+        }]);
+
+        pending.modified = "2025-02-11T17:10:00.000Z".to_string();
+        assert_eq!(check_nonce(63, None, Some(&pending)).nonce_issues, [NonceIssue::Modified {
+            submitted: pending.submission_date.clone(),
+            modified: pending.modified.clone(),
+        }]);
+    }
+
+    #[test]
+    fn test_nonce_gap_does_not_block_signing() {
+        let json = std::fs::read_to_string("../../test/client_tx_response.json").unwrap();
+        let response: crate::api::SafeApiResponse = serde_json::from_str(&json).unwrap();
+        let mut pending = response.results[0].clone();
+        pending.is_executed = false;
+        pending.execution_date = None;
+
+        // Queued behind the next transaction, and edited after submission
+        let warnings = check_nonce(63, Some(60), Some(&pending));
+        assert_eq!(warnings.nonce_issues.len(), 2);
+        assert!(!warnings.blocks_signing());
+
+        assert!(check_nonce(63, Some(64), Some(&pending)).blocks_signing());
+        assert!(check_nonce(63, Some(64), Some(&response.results[0])).blocks_signing());
+    }
+}
@@ -52,31 +52,72 @@ const SAFE_CHAIN_APIS: &[(&str, &str)] = &[
     ("polygon-zkevm", "https://safe-transaction-zkevm.safe.global"),
 ];
 
+const SAFE_NATIVE_CURRENCIES: &[(ChainId, &str)] = &[
+    (42161, "ETH"),
+    (1313161554, "ETH"),
+    (43114, "AVAX"),
+    (8453, "ETH"),
+    (81457, "ETH"),
+    (56, "BNB"),
+    (42220, "CELO"),
+    (1, "ETH"),
+    (100, "xDAI"),
+    (59144, "ETH"),
+    (5000, "MNT"),
+    (143, "MON"),
+    (10, "ETH"),
+    (137, "POL"),
+    (534352, "ETH"),
+    (11155111, "ETH"),
+    (480, "ETH"),
+    (196, "OKB"),
+    (324, "ETH"),
+    (84532, "ETH"),
+    (10200, "xDAI"),
+    (1101, "ETH"),
+];
+
 pub fn get_all_supported_chain_names() -> Vec<String> {
     SAFE_SUPPORTED_CHAINS.iter().map(|(_, chain_name)| chain_name.to_string()).collect()
 }
 
+pub fn get_chain_name(chain_id: ChainId) -> Result<String> {
+    SAFE_SUPPORTED_CHAINS
+        .iter()
+        .find(|&&(id, _)| id == chain_id)
+        .map(|&(_, name)| name.to_string())
+        .ok_or_else(|| format!("unsupported safe chain id - {chain_id}").into())
+}
+
+/// The symbol of the chain's native currency, or "native token" for chains not listed here.
+pub fn get_native_currency(chain_id: ChainId) -> &'static str {
+    SAFE_NATIVE_CURRENCIES
+        .iter()
+        .find(|&&(id, _)| id == chain_id)
+        .map_or("native token", |&(_, symbol)| symbol)
+}
+
 pub fn get_safe_api(chain_id: ChainId) -> Result<String> {
     let chain_name = {
         let chain_names =
             SAFE_SUPPORTED_CHAINS
-                .into_iter()
+                .iter()
                 .filter_map(|(_chain_id, chain_name)| {
                     if *_chain_id == chain_id { Some(chain_name.to_string()) } else { None }
                 })
                 .collect::<Vec<_>>();
-        chain_names.get(0).ok_or("no chain found")?.to_string()
+        chain_names.first().ok_or("no chain found")?.to_string()
     };
 
     let api = {
         let apis =
             SAFE_CHAIN_APIS
-                .into_iter()
+                .iter()
                 .filter_map(|(_chain_name, chain_api)| {
                     if _chain_name == &chain_name { Some(chain_api.to_string()) } else { None }
                 })
                 .collect::<Vec<_>>();
-        apis.get(0).ok_or("chain not found")?.to_string()
+        apis.first().ok_or("chain not found")?.to_string()
     };
 
     Ok(api)
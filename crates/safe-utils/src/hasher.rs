@@ -3,7 +3,7 @@ use crate::{Result, SafeWalletVersion};
 use alloy::{
     dyn_abi::DynSolValue,
     hex,
-    primitives::{Address, B256, ChainId, U256, eip191_hash_message, keccak256},
+    primitives::{Address, B256, Bytes, ChainId, U256, eip191_hash_message, keccak256},
     sol,
     sol_types::SolCall,
 };
@@ -40,6 +40,7 @@ pub struct SafeHasher {
 pub struct MessageHasher {
     string_message: Option<String>,
     bytes_message: Option<B256>,
+    raw_message: Option<Bytes>,
 }
 
 #[derive(Debug)]
@@ -58,6 +59,7 @@ pub struct FullTx {
 }
 
 impl FullTx {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         to: Address,
         value: U256,
@@ -163,6 +165,7 @@ impl CallDataHasher {
 }
 
 impl TxMessageHasher {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         safe_version: SafeWalletVersion,
         to: Address,
@@ -231,18 +234,25 @@ impl SafeHasher {
 
 impl MessageHasher {
     pub fn new(message: String) -> Self {
-        Self { string_message: Some(message), bytes_message: None }
+        Self { string_message: Some(message), bytes_message: None, raw_message: None }
     }
 
     pub fn new_from_bytes(message: B256) -> Self {
-        Self { bytes_message: Some(message), string_message: None }
+        Self { bytes_message: Some(message), string_message: None, raw_message: None }
+    }
+
+    /// Hashes arbitrary bytes with EIP-191, as `personal_sign` does for raw or hex payloads.
+    pub fn new_from_raw_bytes(message: Bytes) -> Self {
+        Self { raw_message: Some(message), string_message: None, bytes_message: None }
     }
 
     pub fn raw_hash(&self) -> B256 {
         if let Some(string_message) = &self.string_message {
             return eip191_hash_message(string_message);
         } else if let Some(bytes_messge) = &self.bytes_message {
-            return bytes_messge.clone();
+            return *bytes_messge;
+        } else if let Some(raw_message) = &self.raw_message {
+            return eip191_hash_message(raw_message);
         }
         unimplemented!();
     }
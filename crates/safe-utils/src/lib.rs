@@ -2,10 +2,12 @@ mod chains;
 mod eip712;
 mod error;
 mod hasher;
+mod signatures;
 mod version;
 
 pub use chains::*;
 pub use eip712::*;
 pub use error::*;
 pub use hasher::*;
+pub use signatures::*;
 pub use version::*;
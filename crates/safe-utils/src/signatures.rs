@@ -0,0 +1,601 @@
+use crate::{DomainHasher, Result, SafeHasher, SafeWalletVersion};
+
+use alloy::primitives::{
+    Address, B256, Bytes, ChainId, PrimitiveSignature, U256, eip191_hash_message, keccak256,
+};
+
+/// Nested contract signatures deeper than this are reported as unverifiable.
+const MAX_CONTRACT_SIGNATURE_DEPTH: usize = 8;
+
+/// How an owner approved a Safe transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureKind {
+    /// `r | s | v` signature of the Safe hash (`v` 27 or 28), or of the `eth_sign` prefixed
+    /// Safe hash (`v` 31 or 32)
+    Ecdsa([u8; 65]),
+    /// The owner called `approveHash` or is the executor (`v` 1)
+    ApprovedHash,
+    /// EIP-1271 signature verified by the owner contract (`v` 0)
+    Contract(Bytes),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerSignature {
+    pub owner: Address,
+    pub kind: SignatureKind,
+}
+
+impl OwnerSignature {
+    /// Recovers the owner of an ECDSA signature of `safe_tx_hash`.
+    pub fn ecdsa(signature: &[u8], safe_tx_hash: B256) -> Result<Self> {
+        let signature: [u8; 65] = signature
+            .try_into()
+            .map_err(|_| format!("expected a 65 byte signature, got {} bytes", signature.len()))?;
+
+        let mut recoverable = signature;
+        let prehash = match signature[64] {
+            27 | 28 => safe_tx_hash,
+            31 | 32 => {
+                recoverable[64] -= 4;
+                eip191_hash_message(safe_tx_hash)
+            }
+            v => return Err(format!("unsupported signature v value {}", v).into()),
+        };
+        let owner = PrimitiveSignature::try_from(&recoverable[..])?
+            .recover_address_from_prehash(&prehash)?;
+
+        Ok(Self { owner, kind: SignatureKind::Ecdsa(signature) })
+    }
+
+    pub fn approved_hash(owner: Address) -> Self {
+        Self { owner, kind: SignatureKind::ApprovedHash }
+    }
+
+    pub fn contract(owner: Address, signature: Bytes) -> Self {
+        Self { owner, kind: SignatureKind::Contract(signature) }
+    }
+}
+
+/// Packs signatures into the `signatures` bytes of `execTransaction`.
+///
+/// `checkNSignatures` requires the 65 byte static parts to be sorted by ascending owner, each
+/// owner once. Contract signatures point, through `s`, to their length-prefixed dynamic part
+/// appended after all static parts.
+pub fn pack_signatures(mut signatures: Vec<OwnerSignature>) -> Result<Bytes> {
+    signatures.sort_by_key(|signature| signature.owner);
+    if let Some(pair) = signatures.windows(2).find(|pair| pair[0].owner == pair[1].owner) {
+        return Err(format!("owner {} signed more than once", pair[0].owner).into());
+    }
+
+    let mut static_parts = Vec::with_capacity(signatures.len() * 65);
+    let mut dynamic_parts = Vec::new();
+    for signature in &signatures {
+        match &signature.kind {
+            SignatureKind::Ecdsa(bytes) => static_parts.extend_from_slice(bytes),
+            SignatureKind::ApprovedHash => {
+                static_parts.extend_from_slice(signature.owner.into_word().as_slice());
+                static_parts.extend_from_slice(&[0u8; 32]);
+                static_parts.push(1);
+            }
+            SignatureKind::Contract(bytes) => {
+                let offset = signatures.len() * 65 + dynamic_parts.len();
+                static_parts.extend_from_slice(signature.owner.into_word().as_slice());
+                static_parts.extend_from_slice(&U256::from(offset).to_be_bytes::<32>());
+                static_parts.push(0);
+                dynamic_parts.extend_from_slice(&U256::from(bytes.len()).to_be_bytes::<32>());
+                dynamic_parts.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    static_parts.extend(dynamic_parts);
+    Ok(static_parts.into())
+}
+
+/// Splits packed `signatures` bytes, such as a confirmation from the transaction service, back
+/// into owner signatures.
+pub fn unpack_signatures(packed: &[u8], safe_tx_hash: B256) -> Result<Vec<OwnerSignature>> {
+    let mut signatures = Vec::new();
+    // Static parts end where the first dynamic part starts
+    let mut static_end = packed.len();
+    let mut position = 0;
+    while position + 65 <= static_end {
+        let part = &packed[position..position + 65];
+        let owner = Address::from_word(B256::from_slice(&part[..32]));
+        let signature = match part[64] {
+            0 => {
+                let offset: usize = U256::from_be_slice(&part[32..64])
+                    .try_into()
+                    .map_err(|_| "contract signature offset out of range")?;
+                let start =
+                    offset.checked_add(32).ok_or("contract signature offset out of range")?;
+                let length: usize = packed
+                    .get(offset..start)
+                    .map(U256::from_be_slice)
+                    .ok_or("contract signature offset out of range")?
+                    .try_into()
+                    .map_err(|_| "contract signature length out of range")?;
+                let data = start
+                    .checked_add(length)
+                    .and_then(|end| packed.get(start..end))
+                    .ok_or("contract signature length out of range")?;
+                static_end = static_end.min(offset);
+                OwnerSignature::contract(owner, Bytes::copy_from_slice(data))
+            }
+            1 => OwnerSignature::approved_hash(owner),
+            _ => OwnerSignature::ecdsa(part, safe_tx_hash)?,
+        };
+        signatures.push(signature);
+        position += 65;
+    }
+
+    if position != static_end {
+        return Err(format!("{} trailing signature bytes", static_end - position).into());
+    }
+    Ok(signatures)
+}
+
+/// Version, owners and threshold of a Safe, for verifying its signatures offline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeOwnership {
+    pub version: SafeWalletVersion,
+    pub owners: Vec<Address>,
+    pub threshold: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InnerSignatureStatus {
+    /// Signed by an owner of the Safe
+    Valid,
+    /// Signed by an address that is not an owner of the Safe
+    NotAnOwner,
+    /// Approved hashes are on-chain state, and contract owners may not be Safes we know of
+    Unverifiable,
+    /// Contract signature of an owner Safe that would be rejected
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerSignature {
+    pub signature: OwnerSignature,
+    pub status: InnerSignatureStatus,
+    /// Verification of the signature when the signer is itself an owner Safe
+    pub nested: Option<ContractSignatureCheck>,
+}
+
+/// Whether a Safe owner's EIP-1271 contract signature would be accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractSignatureCheck {
+    pub safe: Address,
+    /// The `SafeMessage` hash of the owner Safe its owners signed
+    pub message_hash: B256,
+    pub threshold: u64,
+    pub signatures: Vec<InnerSignature>,
+    /// Whether the first `threshold` signatures are sorted by ascending owner, as
+    /// `checkNSignatures` requires
+    pub sorted: bool,
+    pub accepted: bool,
+}
+
+/// A hash a Safe checks the signatures of its owners against, and how it asks its contract owners
+/// to validate it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedHash {
+    /// Version of the checking Safe
+    pub version: SafeWalletVersion,
+    pub hash: B256,
+    /// keccak of the `data` bytes `checkSignatures` is given along with `hash`
+    pub data_hash: B256,
+}
+
+impl CheckedHash {
+    /// The hash of a Safe transaction, checked along with its `encodeTransactionData` preimage.
+    pub fn transaction(version: SafeWalletVersion, safe_tx_hash: B256) -> Self {
+        Self { version, hash: safe_tx_hash, data_hash: safe_tx_hash }
+    }
+
+    /// keccak of the message an owner contract is asked to validate, which an owner Safe wraps in
+    /// its `SafeMessage`.
+    ///
+    /// Safes from 1.5.0 call the EIP-1271 `isValidSignature(bytes32, bytes)`, which the fallback
+    /// handler forwards as `abi.encode(hash)`. Earlier Safes call the legacy
+    /// `isValidSignature(bytes, bytes)` with the `data` they are checked with.
+    pub fn owner_message(&self) -> B256 {
+        if self.version >= SafeWalletVersion::new(1, 5, 0) {
+            keccak256(self.hash)
+        } else {
+            self.data_hash
+        }
+    }
+
+    /// The hash the owner Safe `owner` of `version` checks the signatures of its owners against
+    /// when asked to validate this hash.
+    ///
+    /// From 1.4.0 the fallback handler checks them along with the `SafeMessage` preimage, earlier
+    /// handlers pass on the `data` they are given.
+    pub fn for_owner(&self, owner: Address, version: SafeWalletVersion, chain_id: ChainId) -> Self {
+        let message = self.owner_message();
+        let domain_hash = DomainHasher::new(version.clone(), chain_id, owner).hash();
+        let hash = SafeHasher::new(domain_hash, safe_message_struct_hash(message)).hash();
+        let data_hash = if version >= SafeWalletVersion::new(1, 4, 0) { hash } else { message };
+        Self { version, hash, data_hash }
+    }
+}
+
+/// `SafeMessage(bytes message)` struct hash of a message whose keccak is `message`.
+pub fn safe_message_struct_hash(message: B256) -> B256 {
+    keccak256([keccak256("SafeMessage(bytes message)").as_slice(), message.as_slice()].concat())
+}
+
+/// Verifies the contract signature `signature` of the owner Safe `safe` over `checked`, the hash
+/// of the Safe it owns.
+///
+/// The owners of `safe` sign a `SafeMessage` of `safe`, which depends on the versions of both
+/// Safes. Their signatures are decoded and recovered, owners that are Safes themselves are
+/// verified recursively, and the signature is accepted when the first `threshold` signatures,
+/// sorted by owner, are all valid. `lookup` returns the version, owners and threshold of a Safe,
+/// `None` when it is not a Safe.
+pub fn verify_contract_signature(
+    safe: Address,
+    signature: &[u8],
+    checked: &CheckedHash,
+    chain_id: ChainId,
+    lookup: &dyn Fn(Address) -> Option<SafeOwnership>,
+) -> Result<ContractSignatureCheck> {
+    verify_contract_signature_at_depth(safe, signature, checked, chain_id, lookup, 0)
+}
+
+fn verify_contract_signature_at_depth(
+    safe: Address,
+    signature: &[u8],
+    checked: &CheckedHash,
+    chain_id: ChainId,
+    lookup: &dyn Fn(Address) -> Option<SafeOwnership>,
+    depth: usize,
+) -> Result<ContractSignatureCheck> {
+    let ownership = lookup(safe).ok_or_else(|| format!("{} is not a known Safe", safe))?;
+    let owner_checked = checked.for_owner(safe, ownership.version.clone(), chain_id);
+    let message_hash = owner_checked.hash;
+
+    let mut signatures = Vec::new();
+    for inner in unpack_signatures(signature, message_hash)? {
+        let is_owner = ownership.owners.contains(&inner.owner);
+        let (status, nested) = match &inner.kind {
+            _ if !is_owner => (InnerSignatureStatus::NotAnOwner, None),
+            SignatureKind::Ecdsa(_) => (InnerSignatureStatus::Valid, None),
+            SignatureKind::ApprovedHash => (InnerSignatureStatus::Unverifiable, None),
+            SignatureKind::Contract(_) if depth + 1 >= MAX_CONTRACT_SIGNATURE_DEPTH => {
+                (InnerSignatureStatus::Unverifiable, None)
+            }
+            SignatureKind::Contract(data) => match verify_contract_signature_at_depth(
+                inner.owner,
+                data,
+                &owner_checked,
+                chain_id,
+                lookup,
+                depth + 1,
+            ) {
+                Ok(check) if check.accepted => (InnerSignatureStatus::Valid, Some(check)),
+                Ok(check) => (InnerSignatureStatus::Rejected, Some(check)),
+                Err(_) => (InnerSignatureStatus::Unverifiable, None),
+            },
+        };
+        signatures.push(InnerSignature { signature: inner, status, nested });
+    }
+
+    let threshold = usize::try_from(ownership.threshold).unwrap_or(usize::MAX);
+    // `checkNSignatures` only reads, and requires ordered, the first `threshold` signatures
+    let sorted = signatures[..threshold.min(signatures.len())]
+        .windows(2)
+        .all(|pair| pair[0].signature.owner < pair[1].signature.owner);
+    let accepted = sorted
+        && threshold > 0
+        && signatures.len() >= threshold
+        && signatures[..threshold]
+            .iter()
+            .all(|signature| signature.status == InnerSignatureStatus::Valid);
+
+    Ok(ContractSignatureCheck {
+        safe,
+        message_hash,
+        threshold: ownership.threshold,
+        signatures,
+        sorted,
+        accepted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        hex,
+        primitives::{address, b256},
+        signers::{SignerSync, local::PrivateKeySigner},
+    };
+
+    const SAFE_TX_HASH: B256 =
+        b256!("0xad06b099fca34e51e4886643d95d9a19ace2cd024065efb66662a876e8c40343");
+
+    #[test]
+    fn test_ecdsa_owner_recovery() {
+        let signer = PrivateKeySigner::random();
+
+        let signature = signer.sign_hash_sync(&SAFE_TX_HASH).unwrap().as_bytes();
+        let recovered = OwnerSignature::ecdsa(&signature, SAFE_TX_HASH).unwrap();
+        assert_eq!(recovered.owner, signer.address());
+
+        let mut signature = signer.sign_message_sync(SAFE_TX_HASH.as_slice()).unwrap().as_bytes();
+        signature[64] += 4;
+        let recovered = OwnerSignature::ecdsa(&signature, SAFE_TX_HASH).unwrap();
+        assert_eq!(recovered.owner, signer.address());
+
+        assert!(OwnerSignature::ecdsa(&signature[..64], SAFE_TX_HASH).is_err());
+    }
+
+    #[test]
+    fn test_pack_sorts_by_owner_and_appends_dynamic_parts() {
+        let low = address!("0x1111111111111111111111111111111111111111");
+        let high = address!("0x2222222222222222222222222222222222222222");
+        let packed = pack_signatures(vec![
+            OwnerSignature::contract(high, Bytes::from_static(&[0xaa, 0xbb])),
+            OwnerSignature::approved_hash(low),
+        ])
+        .unwrap();
+
+        let expected = concat!(
+            // approved hash of the lower owner
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "01",
+            // contract signature pointing past both static parts
+            "0000000000000000000000002222222222222222222222222222222222222222",
+            "0000000000000000000000000000000000000000000000000000000000000082",
+            "00",
+            // dynamic part
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "aabb",
+        );
+        assert_eq!(hex::encode(&packed), expected);
+    }
+
+    #[test]
+    fn test_pack_rejects_duplicate_owner() {
+        let owner = address!("0x1111111111111111111111111111111111111111");
+        let signatures =
+            vec![OwnerSignature::approved_hash(owner), OwnerSignature::approved_hash(owner)];
+        assert!(pack_signatures(signatures).is_err());
+    }
+
+    #[test]
+    fn test_unpack_round_trip() {
+        let signer = PrivateKeySigner::random();
+        let signatures = vec![
+            OwnerSignature::ecdsa(
+                &signer.sign_hash_sync(&SAFE_TX_HASH).unwrap().as_bytes(),
+                SAFE_TX_HASH,
+            )
+            .unwrap(),
+            OwnerSignature::approved_hash(address!("0x1111111111111111111111111111111111111111")),
+            OwnerSignature::contract(
+                address!("0x2222222222222222222222222222222222222222"),
+                Bytes::from_static(&[0xaa, 0xbb, 0xcc]),
+            ),
+        ];
+
+        let packed = pack_signatures(signatures.clone()).unwrap();
+        let mut expected = signatures;
+        expected.sort_by_key(|signature| signature.owner);
+        assert_eq!(unpack_signatures(&packed, SAFE_TX_HASH).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unpack_rejects_overflowing_contract_signature() {
+        let owner = address!("0x2222222222222222222222222222222222222222").into_word();
+        let contract_signature = |offset: U256, length: U256| {
+            [owner.as_slice(), &offset.to_be_bytes::<32>(), &[0], &length.to_be_bytes::<32>()]
+                .concat()
+        };
+
+        // The largest offset that fits in a usize, and a length running past the end of memory
+        let offset = U256::MAX >> (256 - usize::BITS);
+        for (offset, length) in [(offset, U256::ZERO), (U256::from(65), offset)] {
+            let packed = contract_signature(offset, length);
+            assert!(
+                unpack_signatures(&packed, SAFE_TX_HASH)
+                    .unwrap_err()
+                    .to_string()
+                    .ends_with("out of range")
+            );
+        }
+    }
+
+    fn ecdsa(signer: &PrivateKeySigner, hash: B256) -> OwnerSignature {
+        OwnerSignature::ecdsa(&signer.sign_hash_sync(&hash).unwrap().as_bytes(), hash).unwrap()
+    }
+
+    const OUTER_SAFE: Address = address!("0x1c694Fc3006D81ff4a56F97E1b99529066a23725");
+
+    fn v(minor: u64) -> SafeWalletVersion {
+        SafeWalletVersion::new(1, minor, if minor == 4 { 1 } else { 0 })
+    }
+
+    fn domain_separator(safe: Address, version: SafeWalletVersion) -> B256 {
+        DomainHasher::new(version, 1, safe).hash()
+    }
+
+    /// `encodeTransactionData` of the outer Safe, whose keccak is the hash owners approve.
+    fn transaction_data(version: SafeWalletVersion) -> Vec<u8> {
+        [&[0x19, 0x01], domain_separator(OUTER_SAFE, version).as_slice(), SAFE_TX_HASH.as_slice()]
+            .concat()
+    }
+
+    /// `encodeMessageDataForSafe` of the `CompatibilityFallbackHandler`.
+    fn message_data_for_safe(safe: Address, version: SafeWalletVersion, message: &[u8]) -> Vec<u8> {
+        let typehash = keccak256("SafeMessage(bytes message)");
+        let safe_message_hash = keccak256([typehash, keccak256(message)].concat());
+        [&[0x19, 0x01], domain_separator(safe, version).as_slice(), safe_message_hash.as_slice()]
+            .concat()
+    }
+
+    /// `getMessageHashForSafe` of the `CompatibilityFallbackHandler`.
+    fn message_hash_for_safe(safe: Address, version: SafeWalletVersion, message: &[u8]) -> B256 {
+        keccak256(message_data_for_safe(safe, version, message))
+    }
+
+    #[test]
+    fn test_verify_contract_signature() {
+        let inner_safe = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+        let mut owners = [PrivateKeySigner::random(), PrivateKeySigner::random()];
+        owners.sort_by_key(|owner| owner.address());
+        let ownership = SafeOwnership {
+            version: v(3),
+            owners: owners.iter().map(|owner| owner.address()).collect(),
+            threshold: 2,
+        };
+        let lookup = |safe: Address| (safe == inner_safe).then(|| ownership.clone());
+        // A 1.3.0 Safe calls `isValidSignature(bytes, bytes)` with its transaction data
+        let data = transaction_data(v(3));
+        let checked = CheckedHash::transaction(v(3), keccak256(&data));
+        let message_hash = message_hash_for_safe(inner_safe, v(3), &data);
+
+        let both = pack_signatures(owners.iter().map(|owner| ecdsa(owner, message_hash)).collect())
+            .unwrap();
+        let check = verify_contract_signature(inner_safe, &both, &checked, 1, &lookup).unwrap();
+        assert_eq!(check.message_hash, message_hash);
+        assert!(check.sorted);
+        assert!(check.accepted);
+
+        // One signature is below the threshold
+        let one = pack_signatures(vec![ecdsa(&owners[0], message_hash)]).unwrap();
+        let check = verify_contract_signature(inner_safe, &one, &checked, 1, &lookup).unwrap();
+        assert!(!check.accepted);
+
+        // Signatures over the outer hash instead of the SafeMessage are not from owners
+        let wrong =
+            pack_signatures(owners.iter().map(|owner| ecdsa(owner, checked.hash)).collect())
+                .unwrap();
+        let check = verify_contract_signature(inner_safe, &wrong, &checked, 1, &lookup).unwrap();
+        assert!(!check.accepted);
+
+        assert!(
+            verify_contract_signature(
+                address!("0x1111111111111111111111111111111111111111"),
+                &both,
+                &checked,
+                1,
+                &lookup
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_contract_signature_for_each_parent_version() {
+        let inner_safe = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+        let signer = PrivateKeySigner::random();
+        let lookup = |safe: Address| {
+            (safe == inner_safe).then(|| SafeOwnership {
+                version: v(4),
+                owners: vec![signer.address()],
+                threshold: 1,
+            })
+        };
+
+        for parent in [v(1), v(3), v(4), v(5)] {
+            let data = transaction_data(parent.clone());
+            let safe_tx_hash = keccak256(&data);
+            // From 1.5.0 the hash goes through `isValidSignature(bytes32, bytes)`, which the
+            // fallback handler forwards as `abi.encode(hash)`
+            let message = if parent >= v(5) { safe_tx_hash.to_vec() } else { data };
+            let message_hash = message_hash_for_safe(inner_safe, v(4), &message);
+
+            let signature = pack_signatures(vec![ecdsa(&signer, message_hash)]).unwrap();
+            let checked = CheckedHash::transaction(parent.clone(), safe_tx_hash);
+            let check =
+                verify_contract_signature(inner_safe, &signature, &checked, 1, &lookup).unwrap();
+            assert_eq!(check.message_hash, message_hash, "parent {}", parent);
+            assert!(check.accepted, "parent {}", parent);
+        }
+    }
+
+    #[test]
+    fn test_verify_contract_signature_ignores_extra_signatures() {
+        let inner_safe = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+        let mut owners =
+            [PrivateKeySigner::random(), PrivateKeySigner::random(), PrivateKeySigner::random()];
+        owners.sort_by_key(|owner| owner.address());
+        let ownership = SafeOwnership {
+            version: v(3),
+            owners: owners.iter().map(|owner| owner.address()).collect(),
+            threshold: 2,
+        };
+        let lookup = |safe: Address| (safe == inner_safe).then(|| ownership.clone());
+        let data = transaction_data(v(3));
+        let checked = CheckedHash::transaction(v(3), keccak256(&data));
+        let message_hash = message_hash_for_safe(inner_safe, v(3), &data);
+
+        // A sorted prefix meeting the threshold, then an extra signature out of order
+        let packed = [&owners[0], &owners[2], &owners[1]]
+            .iter()
+            .flat_map(|owner| owner.sign_hash_sync(&message_hash).unwrap().as_bytes())
+            .collect::<Vec<_>>();
+        let check = verify_contract_signature(inner_safe, &packed, &checked, 1, &lookup).unwrap();
+        assert_eq!(check.signatures.len(), 3);
+        assert!(check.sorted);
+        assert!(check.accepted);
+
+        // Out of order within the threshold
+        let packed = [&owners[2], &owners[0]]
+            .iter()
+            .flat_map(|owner| owner.sign_hash_sync(&message_hash).unwrap().as_bytes())
+            .collect::<Vec<_>>();
+        let check = verify_contract_signature(inner_safe, &packed, &checked, 1, &lookup).unwrap();
+        assert!(!check.sorted);
+        assert!(!check.accepted);
+    }
+
+    #[test]
+    fn test_verify_nested_contract_signature() {
+        let outer_owner = address!("0x5031f5E2ed384978dca63306dc28A68a6Fc33e81");
+        let inner_owner = address!("0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1");
+        let signer = PrivateKeySigner::random();
+        let data = transaction_data(v(3));
+        let checked = CheckedHash::transaction(v(3), keccak256(&data));
+
+        for outer_version in [v(3), v(4)] {
+            let lookup = |safe: Address| {
+                let (version, owners) = if safe == outer_owner {
+                    (outer_version.clone(), vec![inner_owner])
+                } else if safe == inner_owner {
+                    (v(3), vec![signer.address()])
+                } else {
+                    return None;
+                };
+                Some(SafeOwnership { version, owners, threshold: 1 })
+            };
+
+            let outer_message = message_hash_for_safe(outer_owner, outer_version.clone(), &data);
+            // A 1.3.0 handler checks the owners with the data it was given, from 1.4.0 with the
+            // SafeMessage preimage
+            let inner_data = if outer_version >= v(4) {
+                message_data_for_safe(outer_owner, outer_version.clone(), &data)
+            } else {
+                data.clone()
+            };
+            let inner_message = message_hash_for_safe(inner_owner, v(3), &inner_data);
+            let inner_signature = pack_signatures(vec![ecdsa(&signer, inner_message)]).unwrap();
+            let signature =
+                pack_signatures(vec![OwnerSignature::contract(inner_owner, inner_signature)])
+                    .unwrap();
+
+            let check =
+                verify_contract_signature(outer_owner, &signature, &checked, 1, &lookup).unwrap();
+            assert_eq!(check.message_hash, outer_message);
+            assert!(check.accepted, "outer owner {}", outer_version);
+            let nested = check.signatures[0].nested.as_ref().unwrap();
+            assert_eq!(nested.safe, inner_owner);
+            assert_eq!(nested.message_hash, inner_message);
+        }
+    }
+}
@@ -225,3 +225,115 @@ fn test_nested_transaction_cli() {
         "Nested transaction hash not found in output"
     );
 }
+
+#[test]
+fn test_safe_hash_tx_signing_device_output() {
+    let output = Command::new("cargo")
+            .arg("run")
+            .arg("-p")
+            .arg("safe-hash")
+            .arg("--")
+            .arg("tx")
+            .arg("--offline")
+            .arg("--chain")
+            .arg("ethereum")
+            .arg("--nonce")
+            .arg("63")
+            .arg("--safe-address")
+            .arg("0x1c694Fc3006D81ff4a56F97E1b99529066a23725")
+            .arg("--to")
+            .arg("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")
+            .arg("--data")
+            .arg("0xa9059cbb00000000000000000000000092d0ebaf7eb707f0650f9471e61348f4656c29bc00000000000000000000000000000000000000000000000000000005d21dba00")
+            .arg("--safe-version")
+            .arg("1.3.0")
+            .arg("--device")
+            .arg("ledger-legacy")
+            .output()
+            .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+
+    // Domain hash, upper-cased as on the device
+    assert!(stdout.contains("0x1655E94A9BCC5A957DAA1ACAE692B4C22E7AAF146B4DEB9194F8221D2F09D8C3"));
+    // Safe transaction hash as a binary string literal
+    assert!(stdout.contains(
+        r"\xad\x06\xb0\x99\xfc\xa3NQ\xe4\x88fC\xd9]\x9a\x19\xac\xe2\xcd\x02@e\xef\xb6fb\xa8v\xe8\xc4\x03C"
+    ));
+}
+
+#[test]
+fn test_safe_hash_module_delay_hash() {
+    // execTransactionFromModule wrapping a USDC transfer, as queued on a Delay module
+    let output = Command::new("cargo")
+            .arg("run")
+            .arg("-p")
+            .arg("safe-hash")
+            .arg("--")
+            .arg("module")
+            .arg("--chain")
+            .arg("ethereum")
+            .arg("--data")
+            .arg("0x468721a7000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb480000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000044a9059cbb00000000000000000000000092d0ebaf7eb707f0650f9471e61348f4656c29bc00000000000000000000000000000000000000000000000000000005d21dba0000000000000000000000000000000000000000000000000000000000")
+            .output()
+            .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+
+    assert!(stdout.contains("execTransactionFromModule"));
+    assert!(stdout.contains("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+    assert!(stdout.contains("0x015f0a3eef487d828b6ce9b80d6355480a816499302f0165f65ce60754d0fbbb"));
+}
+
+#[test]
+fn test_sign_msg_refuses_siwe_message_for_another_chain() {
+    let dir = std::env::temp_dir();
+    let message = dir.join(format!("safe-hash-siwe-{}.txt", std::process::id()));
+    let mnemonic = dir.join(format!("safe-hash-siwe-mnemonic-{}.txt", std::process::id()));
+    std::fs::write(
+        &message,
+        "example.com wants you to sign in with your Ethereum account:\n\
+         0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1\n\n\n\
+         URI: https://example.com\n\
+         Version: 1\n\
+         Chain ID: 1\n\
+         Nonce: 32891756\n\
+         Issued At: 2025-05-08T16:37:09.140Z",
+    )
+    .unwrap();
+    std::fs::write(&mnemonic, "test test test test test test test test test test test junk")
+        .unwrap();
+
+    let sign = |override_warnings: bool| {
+        let mut command = Command::new("cargo");
+        command.args(["run", "-p", "safe-hash", "--", "sign"]);
+        if override_warnings {
+            command.arg("--sign-despite-warnings");
+        }
+        command
+            .arg("--mnemonic-file")
+            .arg(&mnemonic)
+            .args(["msg", "--safe-version", "1.3.0", "--chain", "sepolia"])
+            .args(["--safe-address", "0x657ff0D4eC65D82b2bC1247b0a558bcd2f80A0f1"])
+            .arg("--input-file")
+            .arg(&message)
+            .output()
+            .expect("Failed to execute command")
+    };
+    let refused = sign(false);
+    let signed = sign(true);
+    std::fs::remove_file(&message).unwrap();
+    std::fs::remove_file(&mnemonic).unwrap();
+
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("Refusing to sign"));
+    assert!(!String::from_utf8_lossy(&refused.stdout).contains("Signing As:"));
+    assert!(signed.status.success(), "Command failed: {}", String::from_utf8_lossy(&signed.stderr));
+    assert!(String::from_utf8_lossy(&signed.stdout).contains("Signing As:"));
+}